@@ -1,27 +1,20 @@
-use std::sync::{Arc, Mutex, TryLockError};
-use std::time::Duration;
-use std::{env, thread};
+use std::env;
 
 use anyhow::{Context, Result, bail};
 use clap::{Parser, command};
 use cpal::Sample;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use indicatif::ProgressBar;
+use pv_cobra_redux::downmix::{Downmix, downmix};
+use pv_cobra_redux::worker::FrameSender;
 
 struct AudioInputProcessor {
     resampler: samplerate::Samplerate,
-    buf: Option<Vec<i16>>,
-    cobra: pv_cobra_redux::Cobra,
-    progress_bar: ProgressBar,
+    frames: FrameSender,
 }
 
 impl AudioInputProcessor {
-    fn new(
-        input_sample_rate: u32,
-        frame_length: usize,
-        channels: usize,
-        access_key: String,
-    ) -> Result<Self> {
+    fn new(input_sample_rate: u32, channels: usize, frames: FrameSender) -> Result<Self> {
         Ok(Self {
             resampler: samplerate::Samplerate::new(
                 samplerate::ConverterType::SincBestQuality,
@@ -29,9 +22,7 @@ impl AudioInputProcessor {
                 pv_cobra_redux::sample_rate() as u32,
                 channels,
             )?,
-            buf: Some(Vec::with_capacity(frame_length)),
-            cobra: pv_cobra_redux::Cobra::new(access_key)?,
-            progress_bar: ProgressBar::new(100),
+            frames,
         })
     }
 }
@@ -100,57 +91,24 @@ fn main() -> Result<()> {
     let device = device?.context("mic device not found")?;
     let access_key = env::var("PICOVOICE_ACCESS_KEY")
         .context("missing environment variable `PICOVOICE_ACCESS_KEY`")?;
-    let channels = device.config.channels();
-    let frame_length = pv_cobra_redux::sample_rate() as usize;
-
-    let proc = Arc::new(Mutex::new(AudioInputProcessor::new(
-        device.config.sample_rate().0,
-        frame_length,
-        channels as usize,
-        access_key,
-    )?));
-
-    let add_samples = move |samples: &[f32]| -> Result<()> {
-        match proc.try_lock() {
-            Ok(mut guard) => {
-                // Resample the stereo audio to the desired sample rate
-                let resampled_stereo = guard.resampler.process(samples)?;
-
-                let resampled_mono: Vec<i16> = if channels == 1 {
-                    resampled_stereo
-                        .iter()
-                        .map(|s| (s * i16::MAX as f32).round() as i16)
-                        .collect()
-                } else {
-                    // convert from stereo to mono
-                    resampled_stereo
-                        .chunks(2) // Iterate over pairs of samples (left, right)
-                        .map(|chunk| {
-                            let left = chunk[0];
-                            let right = chunk[1];
-                            let mono = (left + right) / 2.0; // Average the two channels
-                            (mono * i16::MAX as f32).round() as i16
-                        })
-                        .collect()
-                };
-
-                let buf = guard.buf.as_mut().unwrap();
-                buf.extend_from_slice(&resampled_mono);
-                if buf.len() >= frame_length {
-                    let mut buf = guard.buf.take().unwrap();
-                    let confidence = guard.cobra.process(&buf)?;
-                    buf.clear();
-                    guard.buf = Some(buf);
-                    guard.progress_bar.set_position((confidence * 100.0) as u64);
-                }
-            }
-            Err(TryLockError::WouldBlock) => {
-                eprintln!("microphone stream processing is falling behind");
-            }
-            Err(TryLockError::Poisoned(err)) => {
-                bail!("microphone stream processing lock is poisoned: {err:?}");
-            }
-        }
+    let channels = device.config.channels() as usize;
+
+    // The engine runs on its own thread, so the audio callback only has to
+    // resample and hand the audio off.
+    let (worker, confidences) = pv_cobra_redux::Cobra::new(access_key)?.spawn_worker()?;
+    let mut proc =
+        AudioInputProcessor::new(device.config.sample_rate().0, channels, worker.sender())?;
+
+    let mut add_samples = move |samples: &[f32]| -> Result<()> {
+        // Resample the interleaved audio to the desired sample rate
+        let resampled: Vec<i16> = proc
+            .resampler
+            .process(samples)?
+            .iter()
+            .map(|s| (s * i16::MAX as f32).round() as i16)
+            .collect();
+        proc.frames
+            .send(downmix(&resampled, channels, Downmix::Average))?;
         Ok(())
     };
 
@@ -200,7 +158,10 @@ fn main() -> Result<()> {
     println!("VAD confidence:");
     stream.play()?;
 
-    loop {
-        thread::sleep(Duration::from_secs(1));
+    let progress_bar = ProgressBar::new(100);
+    for result in confidences {
+        let (_, confidence) = result?;
+        progress_bar.set_position(confidence.as_percent() as u64);
     }
+    Ok(())
 }
@@ -0,0 +1,606 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use sha2::{Digest, Sha256};
+
+/// Environment variables that change what gets built, see below.
+const ENV_OVERRIDES: &[&str] = &[
+    "COBRA_RESOURCES_DIR",
+    "PV_COBRA_LIB_PATH",
+    "PV_COBRA_INCLUDE_DIR",
+    "PV_COBRA_OFFLINE",
+    "PV_COBRA_MANIFEST",
+    "PV_COBRA_DOWNLOAD_URL",
+    "PV_COBRA_LIB_SHA256",
+    "COBRA_TARGET_LIB_DIR",
+    "COBRA_LIB_MAP",
+    "COBRA_CPU",
+];
+
+/// The Cobra submodule, which lives at the root of the repository, or another
+/// checkout of Cobra's repository named by `COBRA_RESOURCES_DIR`.
+fn resources_dir() -> PathBuf {
+    match env::var_os("COBRA_RESOURCES_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from("../resources/cobra"),
+    }
+}
+
+/// Libraries and headers can also come from outside the submodule, e.g. for
+/// vendored or air-gapped builds:
+///
+/// - `PV_COBRA_LIB_PATH`: the library to link, named as Picovoice ships it
+///   (e.g. `libpv_cobra.so`);
+/// - `PV_COBRA_INCLUDE_DIR`: the directory holding `pv_cobra.h`, for the
+///   `regenerate-bindings` feature.
+///
+/// Which of the submodule's libraries gets linked can also be picked by hand,
+/// e.g. when cross-compiling for a particular board, with the directory under
+/// `lib` to use (e.g. `raspberry-pi/cortex-a72-aarch64`) in either:
+///
+/// - `COBRA_TARGET_LIB_DIR`;
+/// - a mapping file named by `COBRA_LIB_MAP`, with one `<target> = <dir>` line
+///   per target triple, e.g. `aarch64-unknown-linux-gnu = jetson/cortex-a57-aarch64`.
+///   Blank lines and lines starting with `#` are ignored.
+///
+/// Absolute directories can point outside the submodule altogether.
+///
+/// `PV_COBRA_MANIFEST` names a file of checksums, in `sha256sum` format, that
+/// the library has to match. Entries are keyed by the library's path in the
+/// submodule's `lib` directory (e.g. `linux/x86_64/libpv_cobra.so`), or by its
+/// file name for `PV_COBRA_LIB_PATH`.
+///
+/// With the `system-lib` feature, an installed library is found through
+/// pkg-config instead. With `download-lib`, it's downloaded and checked against
+/// `PV_COBRA_LIB_SHA256`, unless `PV_COBRA_OFFLINE` is set.
+///
+/// With `no-lib`, targets Cobra doesn't support build with stubs in place of
+/// the library, rather than failing.
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(cobra_no_lib)");
+    // docs.rs builds have neither network access nor the submodule, and
+    // don't need the library anyway.
+    if env::var_os("DOCS_RS").is_some() || cfg!(feature = "docs-only") {
+        return;
+    }
+
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    if target_arch == "wasm32" && env::var_os("CARGO_FEATURE_WASM").is_some() {
+        // Instantiated from JavaScript instead, with its own linear memory.
+        return;
+    }
+
+    for var in ENV_OVERRIDES {
+        println!("cargo:rerun-if-env-changed={var}");
+    }
+    let download = cfg!(feature = "download-lib") && env::var_os("PV_COBRA_OFFLINE").is_none();
+    let external =
+        (env::var_os("PV_COBRA_LIB_PATH").is_some() || cfg!(feature = "system-lib") || download)
+            && (cfg!(not(feature = "regenerate-bindings"))
+                || env::var_os("PV_COBRA_INCLUDE_DIR").is_some());
+    let resources_dir = resources_dir();
+    let in_repository = fs::read_to_string("../.gitmodules")
+        .is_ok_and(|gitmodules| gitmodules.contains("resources/cobra"));
+    if !external
+        && env::var_os("COBRA_RESOURCES_DIR").is_none()
+        && !resources_dir.join(".git").exists()
+        && in_repository
+    {
+        let _ = Command::new("git")
+            .args(["submodule", "update", "--init"])
+            .status();
+    }
+    // The submodule isn't part of the published crate.
+    if !external && !resources_dir.join("lib").exists() {
+        unavailable(format!(
+            "Cobra's libraries weren't found in {:?}. Outside this crate's repository, \
+             e.g. when built from crates.io, set PV_COBRA_LIB_PATH to the library, or \
+             COBRA_RESOURCES_DIR to a checkout of https://github.com/Picovoice/cobra, \
+             or enable the download-lib or system-lib feature.",
+            resources_dir
+        ));
+        return;
+    }
+    // Directories, so that adding or swapping a library anywhere in them is
+    // picked up too.
+    for dir in ["include", "lib"] {
+        let dir = resources_dir.join(dir);
+        if dir.exists() {
+            println!("cargo:rerun-if-changed={}", dir.display());
+        }
+    }
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default();
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    #[cfg(feature = "regenerate-bindings")]
+    generate_bindings(&out_dir);
+
+    if cfg!(feature = "system-lib") {
+        if cfg!(feature = "embed-lib") {
+            panic!("The system-lib and embed-lib features can't be enabled together");
+        }
+        #[cfg(feature = "system-lib")]
+        link_system_lib();
+        return;
+    }
+
+    if target_os == "ios" {
+        if let Err(reason) = link_ios(&target_arch) {
+            unavailable(reason);
+        }
+        return;
+    }
+    let link_static = env::var_os("CARGO_FEATURE_STATIC").is_some();
+    if target_env == "musl"
+        && !link_static
+        && let Err(reason) = check_musl()
+    {
+        unavailable(reason);
+        return;
+    }
+
+    let slice = match env::var_os("PV_COBRA_LIB_PATH") {
+        Some(_) => None,
+        None => match lib_slice(&target_os, &target_arch) {
+            Ok(slice) => Some(slice),
+            Err(reason) => {
+                unavailable(reason);
+                return;
+            }
+        },
+    };
+    let src_lib_path = match &slice {
+        None => PathBuf::from(env::var_os("PV_COBRA_LIB_PATH").unwrap()),
+        #[cfg(feature = "download-lib")]
+        Some(slice) if download => download_lib(slice, &out_dir),
+        Some(slice) => resources_dir.join("lib").join(slice),
+    };
+    let src_lib_path = if link_static {
+        static_archive(&src_lib_path)
+    } else {
+        src_lib_path
+    };
+    if !src_lib_path.exists() {
+        unavailable(format!("Expected library not found at {:?}", src_lib_path));
+        return;
+    }
+    let src_lib_dir = src_lib_path.parent().unwrap();
+    let lib_name = src_lib_path.file_name().unwrap().to_str().unwrap();
+    let lib_path = out_dir.join(lib_name);
+    copy(&src_lib_path, &lib_path);
+    println!("cargo:rerun-if-changed={}", src_lib_path.display());
+
+    // The copy, as that's what is linked.
+    let checksum = sha256(&fs::read(&lib_path).unwrap());
+    let key = match &slice {
+        Some(slice) => match slice.rsplit_once('/') {
+            Some((dir, _)) => format!("{dir}/{lib_name}"),
+            None => lib_name.to_string(),
+        },
+        None => lib_name.to_string(),
+    };
+    if let Some(manifest) = env::var_os("PV_COBRA_MANIFEST") {
+        verify_checksum(Path::new(&manifest), &key, &checksum);
+    }
+
+    if env::var_os("CARGO_FEATURE_EMBED_LIB").is_some() {
+        // Included in the crate as `EMBEDDED_LIB`, which the dependent crate
+        // extracts and loads at runtime.
+        record_lib(&key, &checksum);
+        println!("cargo:rustc-env=COBRA_EMBEDDED_LIB={}", lib_path.display());
+    } else if env::var_os("CARGO_FEATURE_DLOPEN").is_some() {
+        // Loaded at runtime instead, from wherever the dependent crate says.
+        // The search path below still lets `cargo run` and `cargo test` find
+        // the copy in OUT_DIR.
+    } else if link_static {
+        record_lib(&key, &checksum);
+        println!("cargo:rustc-link-lib=static=pv_cobra");
+    } else if target_os == "windows" {
+        record_lib(&key, &checksum);
+        link_windows(src_lib_dir, &out_dir, &target_env);
+    } else {
+        record_lib(&key, &checksum);
+        println!("cargo:rustc-link-lib=dylib=pv_cobra");
+    }
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+}
+
+/// Fails the build unless `manifest` lists `checksum` for `key`.
+fn verify_checksum(manifest: &Path, key: &str, checksum: &str) {
+    println!("cargo:rerun-if-changed={}", manifest.display());
+    let manifest = fs::read_to_string(manifest)
+        .unwrap_or_else(|e| panic!("Failed to read {:?}: {}", manifest, e));
+    let expected = manifest.lines().find_map(|line| {
+        let (expected, path) = line.split_once(char::is_whitespace)?;
+        (path.trim_start().trim_start_matches('*') == key).then_some(expected)
+    });
+    match expected {
+        Some(expected) if expected.eq_ignore_ascii_case(checksum) => {}
+        Some(expected) => panic!(
+            "Checksum mismatch for {key}: the manifest expects {expected}, but the library is \
+             {checksum}"
+        ),
+        None => panic!("No checksum for {key} in PV_COBRA_MANIFEST"),
+    }
+}
+
+/// Makes which library was linked available to the crate, see
+/// `LINKED_LIB` and `LINKED_LIB_SHA256`.
+fn record_lib(key: &str, checksum: &str) {
+    println!("cargo:rustc-env=COBRA_LINKED_LIB={key}");
+    println!("cargo:rustc-env=COBRA_LINKED_LIB_SHA256={checksum}");
+}
+
+fn sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Fails the build because there's no library to link, giving `reason`, unless
+/// the `no-lib` feature is on, in which case the crate is built without one and
+/// fails at runtime instead.
+fn unavailable(reason: String) {
+    if cfg!(feature = "no-lib") {
+        println!("cargo:rustc-cfg=cobra_no_lib");
+    } else {
+        panic!("{reason}");
+    }
+}
+
+/// The library for the target, relative to the `lib` directory of the
+/// submodule, or why there's none.
+fn lib_slice(target_os: &str, target_arch: &str) -> Result<String, String> {
+    if let Some(lib_dir) = configured_lib_dir() {
+        let lib_name = match target_os {
+            "macos" => "libpv_cobra.dylib",
+            "windows" => "libpv_cobra.dll",
+            _ => "libpv_cobra.so",
+        };
+        return Ok(format!("{lib_dir}/{lib_name}"));
+    }
+    let (lib_dir, lib_name) = match (target_os, target_arch) {
+        ("linux", "x86_64") => ("linux/x86_64".to_string(), "libpv_cobra.so"),
+        // Jetson boards can't be told apart from a Pi at build time, hence
+        // the feature.
+        ("linux", "aarch64") if env::var_os("CARGO_FEATURE_JETSON").is_some() => {
+            if !pi_features().is_empty() {
+                panic!("The jetson and pi-* features can't be enabled together");
+            }
+            ("jetson/cortex-a57-aarch64".to_string(), "libpv_cobra.so")
+        }
+        ("linux", "aarch64") => (
+            format!("raspberry-pi/{}-aarch64", raspberry_pi_cpu()),
+            "libpv_cobra.so",
+        ),
+        ("linux", "arm") => {
+            let features = env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default();
+            if !features.split(',').any(|feature| feature == "v7") {
+                return Err("Unsupported 32-bit ARM target: Cobra needs ARMv7 or later \
+                            (Raspberry Pi 3 onwards)."
+                    .to_string());
+            }
+            (
+                format!("raspberry-pi/{}", raspberry_pi_cpu()),
+                "libpv_cobra.so",
+            )
+        }
+        // The library must also be packaged into the app's `jniLibs/<abi>`
+        // directory so that it can be found at runtime.
+        ("android", "aarch64") => ("android/arm64-v8a".to_string(), "libpv_cobra.so"),
+        ("android", "arm") => ("android/armeabi-v7a".to_string(), "libpv_cobra.so"),
+        ("android", "x86") => ("android/x86".to_string(), "libpv_cobra.so"),
+        ("android", "x86_64") => ("android/x86_64".to_string(), "libpv_cobra.so"),
+        ("macos", "x86_64") => ("mac/x86_64".to_string(), "libpv_cobra.dylib"),
+        ("macos", "aarch64") => ("mac/arm64".to_string(), "libpv_cobra.dylib"),
+        ("windows", "x86_64") => ("windows/amd64".to_string(), "libpv_cobra.dll"),
+        // Cobra's WebAssembly build is an Emscripten module with a linear
+        // memory of its own, so there's nothing for a wasm32 Rust module to
+        // link against.
+        (_, "wasm32") => {
+            return Err(
+                "Unsupported target: Cobra's WebAssembly build can't be linked into a \
+                        Rust wasm module. Enable the wasm feature to drive it through \
+                        JavaScript instead."
+                    .to_string(),
+            );
+        }
+        _ => return Err(format!("Unsupported target: {target_os} {target_arch}")),
+    };
+    Ok(format!("{lib_dir}/{lib_name}"))
+}
+
+/// The static archive to link in place of the shared library at `lib_path`,
+/// for the `static` feature. Picovoice only ships these for some targets; an
+/// archive of your own can be given with `PV_COBRA_LIB_PATH`.
+fn static_archive(lib_path: &Path) -> PathBuf {
+    if lib_path.extension().is_some_and(|ext| ext == "a") {
+        return lib_path.to_path_buf();
+    }
+    let archive = lib_path.with_file_name("libpv_cobra.a");
+    if !archive.exists() {
+        panic!(
+            "The static feature needs libpv_cobra.a, but there's none in {:?}. \
+             Build without it to link the shared library instead.",
+            lib_path.parent().unwrap()
+        );
+    }
+    archive
+}
+
+/// Where `download-lib` fetches libraries from by default, laid out like the
+/// submodule's `lib` directory. `PV_COBRA_DOWNLOAD_URL` overrides it, e.g. for
+/// a mirror.
+#[cfg(feature = "download-lib")]
+const DOWNLOAD_URL: &str = "https://raw.githubusercontent.com/Picovoice/cobra/main/lib";
+
+/// Large enough for any of Cobra's libraries.
+#[cfg(feature = "download-lib")]
+const MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Downloads the library at `slice` into `OUT_DIR`, unless an earlier build
+/// already did. The download has to match `PV_COBRA_LIB_SHA256`, as the
+/// libraries upstream change without notice.
+#[cfg(feature = "download-lib")]
+fn download_lib(slice: &str, out_dir: &Path) -> PathBuf {
+    let base = env::var("PV_COBRA_DOWNLOAD_URL").unwrap_or_else(|_| DOWNLOAD_URL.to_string());
+    let url = format!("{}/{}", base.trim_end_matches('/'), slice);
+    let expected = env::var("PV_COBRA_LIB_SHA256").unwrap_or_else(|_| {
+        panic!(
+            "The download-lib feature needs PV_COBRA_LIB_SHA256 set to the SHA-256 of {url}. \
+             Set PV_COBRA_OFFLINE to use the submodule instead."
+        )
+    });
+    let expected = expected.trim().to_ascii_lowercase();
+
+    let dir = out_dir.join("download");
+    fs::create_dir_all(&dir).unwrap_or_else(|e| panic!("Failed to create {:?}: {}", dir, e));
+    let path = dir.join(Path::new(slice).file_name().unwrap());
+    if fs::read(&path).is_ok_and(|bytes| sha256(&bytes) == expected) {
+        return path;
+    }
+    let bytes = ureq::get(&url)
+        .call()
+        .and_then(|mut response| {
+            response
+                .body_mut()
+                .with_config()
+                .limit(MAX_DOWNLOAD_SIZE)
+                .read_to_vec()
+        })
+        .unwrap_or_else(|e| panic!("Failed to download {url}: {e}"));
+    let actual = sha256(&bytes);
+    if actual != expected {
+        panic!("Checksum mismatch for {url}: expected {expected}, got {actual}");
+    }
+    fs::write(&path, bytes).unwrap_or_else(|e| panic!("Failed to write {:?}: {}", path, e));
+    path
+}
+
+/// Links the library installed on the system, as described by its
+/// `pv_cobra.pc`. `PKG_CONFIG_PATH` points pkg-config at libraries installed
+/// elsewhere than the usual prefixes.
+#[cfg(feature = "system-lib")]
+fn link_system_lib() {
+    if let Err(err) = pkg_config::Config::new().probe("pv_cobra") {
+        panic!("Couldn't find libpv_cobra with pkg-config: {err}");
+    }
+}
+
+/// Generates bindings from Cobra's headers, which needs libclang. They
+/// replace the committed `src/bindings.rs` when copied over it.
+#[cfg(feature = "regenerate-bindings")]
+fn generate_bindings(out_dir: &Path) {
+    let include_dir = match env::var_os("PV_COBRA_INCLUDE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => resources_dir().join("include"),
+    };
+    let header = include_dir.join("pv_cobra.h");
+    bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        // Leave out everything the headers pull in from the C library.
+        .allowlist_function("pv_cobra_.*|pv_sample_rate|pv_status_to_string|pv_.*_error_stack")
+        .allowlist_type("pv_cobra_t|pv_status_t")
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .generate()
+        .expect("Failed to generate bindings")
+        .write_to_file(out_dir.join("bindings.rs"))
+        .expect("Failed to write bindings");
+}
+
+/// The library directory picked with `COBRA_TARGET_LIB_DIR`, or for the target
+/// in the `COBRA_LIB_MAP` file.
+fn configured_lib_dir() -> Option<String> {
+    if let Ok(lib_dir) = env::var("COBRA_TARGET_LIB_DIR") {
+        return Some(lib_dir);
+    }
+    let map = PathBuf::from(env::var_os("COBRA_LIB_MAP")?);
+    println!("cargo:rerun-if-changed={}", map.display());
+    let contents =
+        fs::read_to_string(&map).unwrap_or_else(|e| panic!("Failed to read {:?}: {}", map, e));
+    let target = env::var("TARGET").unwrap();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((triple, lib_dir)) = line.split_once('=') else {
+            panic!(
+                "Invalid line in {:?}, expected `<target> = <dir>`: {line}",
+                map
+            );
+        };
+        if triple.trim() == target {
+            return Some(lib_dir.trim().to_string());
+        }
+    }
+    None
+}
+
+/// Raspberry Pi CPUs Cobra has builds for, in both 32 and 64 bits.
+const RASPBERRY_PI_CPUS: &[&str] = &["cortex-a53", "cortex-a72", "cortex-a76"];
+
+/// Picks the Raspberry Pi library to link, from the first of:
+///
+/// - the `COBRA_CPU` environment variable, e.g. `cortex-a72`;
+/// - a `pi-<cpu>` feature, e.g. `pi-cortex-a72`;
+/// - `-C target-cpu`, if it names a supported CPU, or is `native` and the
+///   build isn't cross-compiling, in which case the host's CPU is used;
+/// - the cortex-a53 build, which runs on every Pi from the 3 onwards.
+///
+/// ARMv6 targets such as `arm-unknown-linux-gnueabihf` (Pi Zero and 1) have
+/// no Cobra build.
+fn raspberry_pi_cpu() -> String {
+    if let Ok(cpu) = env::var("COBRA_CPU") {
+        if !RASPBERRY_PI_CPUS.contains(&cpu.as_str()) {
+            panic!("Unsupported COBRA_CPU {cpu:?}, expected one of {RASPBERRY_PI_CPUS:?}");
+        }
+        return cpu;
+    }
+    let features = pi_features();
+    match features[..] {
+        [] => {}
+        [cpu] => return cpu.to_string(),
+        _ => panic!("Conflicting Raspberry Pi features enabled: {features:?}"),
+    }
+    match target_cpu().as_deref() {
+        Some(cpu) if RASPBERRY_PI_CPUS.contains(&cpu) => return cpu.to_string(),
+        Some("native") if env::var("HOST") == env::var("TARGET") => {
+            if let Some(cpu) = host_cpu() {
+                return cpu.to_string();
+            }
+        }
+        _ => {}
+    }
+    "cortex-a53".to_string()
+}
+
+/// The CPUs of the `pi-<cpu>` features that are enabled.
+fn pi_features() -> Vec<&'static str> {
+    RASPBERRY_PI_CPUS
+        .iter()
+        .copied()
+        .filter(|cpu| {
+            let feature = format!("CARGO_FEATURE_PI_{}", cpu.to_uppercase().replace('-', "_"));
+            env::var_os(feature).is_some()
+        })
+        .collect()
+}
+
+/// The `-C target-cpu` passed to rustc, if any.
+fn target_cpu() -> Option<String> {
+    let flags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    let mut flags = flags.split('\x1f');
+    while let Some(flag) = flags.next() {
+        let flag = match flag {
+            "-C" | "--codegen" => flags.next()?,
+            flag => flag.strip_prefix("-C").unwrap_or(flag),
+        };
+        if let Some(cpu) = flag.strip_prefix("target-cpu=") {
+            return Some(cpu.to_string());
+        }
+    }
+    None
+}
+
+/// The Raspberry Pi CPU of the build machine, going by the ARM part number
+/// in `/proc/cpuinfo`.
+fn host_cpu() -> Option<&'static str> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    let part = cpuinfo
+        .lines()
+        .find_map(|line| line.strip_prefix("CPU part")?.split(':').nth(1))?;
+    match part.trim() {
+        "0xd03" => Some("cortex-a53"),
+        "0xd08" => Some("cortex-a72"),
+        "0xd0b" => Some("cortex-a76"),
+        _ => None,
+    }
+}
+
+/// iOS builds come as an xcframework, with one slice for devices and another
+/// for the simulator, which is linked as a framework rather than copied.
+fn link_ios(target_arch: &str) -> Result<(), String> {
+    let simulator = env::var("CARGO_CFG_TARGET_ABI").is_ok_and(|abi| abi == "sim");
+    let slice = match target_arch {
+        "aarch64" if simulator => "ios-arm64_x86_64-simulator",
+        "aarch64" => "ios-arm64",
+        "x86_64" => "ios-arm64_x86_64-simulator",
+        _ => return Err(format!("Unsupported target: ios {target_arch}")),
+    };
+    let framework_dir = resources_dir()
+        .join("lib/ios/PvCobra.xcframework")
+        .join(slice);
+    if !framework_dir.join("PvCobra.framework").exists() {
+        return Err(format!(
+            "Expected framework not found in {:?}",
+            framework_dir
+        ));
+    }
+    let framework_dir = framework_dir
+        .canonicalize()
+        .unwrap_or_else(|e| panic!("Failed to resolve {:?}: {}", framework_dir, e));
+    println!("cargo:rerun-if-changed={}", framework_dir.display());
+    println!(
+        "cargo:rustc-link-search=framework={}",
+        framework_dir.display()
+    );
+    println!("cargo:rustc-link-lib=framework=PvCobra");
+    Ok(())
+}
+
+/// Cobra's Linux builds target glibc and only come as shared libraries, so musl
+/// binaries can't be fully static and must link dynamically instead, i.e. with
+/// `RUSTFLAGS="-C target-feature=-crt-static"`. On Alpine the library also
+/// needs the `gcompat` package at runtime.
+fn check_musl() -> Result<(), String> {
+    let features = env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default();
+    if features.split(',').any(|feature| feature == "crt-static") {
+        return Err(
+            "Cobra is only available as a shared library, which a statically linked \
+             musl binary can't load. Build with \
+             RUSTFLAGS=\"-C target-feature=-crt-static\" to link dynamically, and \
+             install gcompat on Alpine."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Picovoice ships `libpv_cobra.dll`, so the library is linked under that
+/// name. The GNU toolchain links against the DLL directly, or against a
+/// `libpv_cobra.dll.a` import library if one is present. MSVC always needs an
+/// import library, `libpv_cobra.lib`, next to the DLL. Either way the DLL
+/// itself must be on the `PATH` (or next to the executable) at runtime.
+fn link_windows(src_lib_dir: &Path, out_dir: &Path, target_env: &str) {
+    let import_libs: &[&str] = if target_env == "msvc" {
+        &["libpv_cobra.lib", "pv_cobra.lib"]
+    } else {
+        &["libpv_cobra.dll.a", "libpv_cobra.lib"]
+    };
+    let import_lib = import_libs
+        .iter()
+        .map(|name| src_lib_dir.join(name))
+        .find(|path| path.exists());
+    match import_lib {
+        // Both linkers find `-l libpv_cobra` under these names.
+        Some(path) if target_env == "msvc" => copy(&path, &out_dir.join("libpv_cobra.lib")),
+        Some(path) => copy(&path, &out_dir.join("libpv_cobra.dll.a")),
+        None if target_env == "msvc" => panic!(
+            "MSVC needs an import library for libpv_cobra.dll, but none was found in {:?}. \
+             Generate one with `lib /def:libpv_cobra.def /machine:x64 /out:libpv_cobra.lib` \
+             and place it next to the DLL, or build with the GNU toolchain.",
+            src_lib_dir
+        ),
+        None => {}
+    }
+    println!("cargo:rustc-link-lib=dylib=libpv_cobra");
+}
+
+fn copy(src: &Path, dst: &Path) {
+    fs::copy(src, dst).unwrap_or_else(|e| panic!("Failed to copy {:?} to {:?}: {}", src, dst, e));
+}
@@ -0,0 +1,69 @@
+/* automatically generated by rust-bindgen 0.72.1 */
+
+unsafe extern "C" {
+    #[doc = " Audio sample rate accepted by Picovoice."]
+    pub fn pv_sample_rate() -> i32;
+}
+pub const pv_status_t_PV_STATUS_SUCCESS: pv_status_t = 0;
+pub const pv_status_t_PV_STATUS_OUT_OF_MEMORY: pv_status_t = 1;
+pub const pv_status_t_PV_STATUS_IO_ERROR: pv_status_t = 2;
+pub const pv_status_t_PV_STATUS_INVALID_ARGUMENT: pv_status_t = 3;
+pub const pv_status_t_PV_STATUS_STOP_ITERATION: pv_status_t = 4;
+pub const pv_status_t_PV_STATUS_KEY_ERROR: pv_status_t = 5;
+pub const pv_status_t_PV_STATUS_INVALID_STATE: pv_status_t = 6;
+pub const pv_status_t_PV_STATUS_RUNTIME_ERROR: pv_status_t = 7;
+pub const pv_status_t_PV_STATUS_ACTIVATION_ERROR: pv_status_t = 8;
+pub const pv_status_t_PV_STATUS_ACTIVATION_LIMIT_REACHED: pv_status_t = 9;
+pub const pv_status_t_PV_STATUS_ACTIVATION_THROTTLED: pv_status_t = 10;
+pub const pv_status_t_PV_STATUS_ACTIVATION_REFUSED: pv_status_t = 11;
+#[doc = " Status codes."]
+pub type pv_status_t = ::std::os::raw::c_uint;
+unsafe extern "C" {
+    #[doc = " Provides string representations of status codes.\n\n @param status Status code.\n @return String representation."]
+    pub fn pv_status_to_string(status: pv_status_t) -> *const ::std::os::raw::c_char;
+}
+unsafe extern "C" {
+    #[doc = " If a function returns a failure (any pv_status_t other than PV_STATUS_SUCCESS), this function can be called\n to get a series of error messages related to the failure. This function can only be called only once per\n failure status on another function. The memory for `message_stack` must be freed using `pv_free_error_stack`.\n\n Regardless of the return status of this function, if `message_stack` is not `NULL`, then `message_stack`\n contains valid memory. However, a failure status on this function indicates that future error messages\n may not be reported.\n\n @param[out] message_stack Array of messages relating to the failure. Messages are NULL terminated strings.\n                           The array and messages must be freed using `pv_free_error_stack`.\n @param[out] message_stack_depth The number of messages in the `message_stack` array."]
+    pub fn pv_get_error_stack(
+        message_stack: *mut *mut *mut ::std::os::raw::c_char,
+        message_stack_depth: *mut i32,
+    ) -> pv_status_t;
+}
+unsafe extern "C" {
+    #[doc = " This function frees the memory used by error messages allocated by `pv_get_error_stack`.\n\n @param message_stack Array of messages relating to the failure, allocated from `pv_get_error_stack`."]
+    pub fn pv_free_error_stack(message_stack: *mut *mut ::std::os::raw::c_char);
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct pv_cobra {
+    _unused: [u8; 0],
+}
+#[doc = " Forward declaration for Cobra voice activity detection (VAD) engine. It detects speech signals within an incoming\n stream of audio in real-time. It processes incoming audio in consecutive frames and for each frame emits the\n probability of voice activity. The number of samples per frame can be attained by calling `pv_cobra_frame_length()`.\n The incoming audio needs to have a sample rate equal to `pv_sample_rate()` and be 16-bit linearly-encoded. Cobra\n operates on single-channel audio."]
+pub type pv_cobra_t = pv_cobra;
+unsafe extern "C" {
+    #[doc = " Constructor.\n\n @param access_key AccessKey obtained from Picovoice Console (https://console.picovoice.ai/)\n @param object Constructed instance of Cobra.\n @return Status code. Returns `PV_STATUS_INVALID_ARGUMENT` or `PV_STATUS_OUT_OF_MEMORY`,\n `PV_STATUS_RUNTIME_ERROR`, `PV_STATUS_ACTIVATION_ERROR`, `PV_STATUS_ACTIVATION_LIMIT_REACHED`,\n `PV_STATUS_ACTIVATION_THROTTLED`, or `PV_STATUS_ACTIVATION_REFUSED` on failure."]
+    pub fn pv_cobra_init(
+        access_key: *const ::std::os::raw::c_char,
+        object: *mut *mut pv_cobra_t,
+    ) -> pv_status_t;
+}
+unsafe extern "C" {
+    #[doc = " Destructor.\n\n @param object Cobra object."]
+    pub fn pv_cobra_delete(object: *mut pv_cobra_t);
+}
+unsafe extern "C" {
+    #[doc = " Processes a frame of the incoming audio stream and emits the probability of voice activity.\n\n @param object Cobra object.\n @param pcm A frame of audio samples. The number of samples per frame can be attained by calling\n `pv_cobra_frame_length()`. The incoming audio needs to have a sample rate equal to `pv_sample_rate()` and be 16-bit\n linearly-encoded. Cobra operates on single-channel audio.\n @param[out] is_voiced Probability of voice activity. It is a floating-point number within [0, 1].\n @return Returns `PV_STATUS_INVALID_ARGUMENT` or `PV_STATUS_OUT_OF_MEMORY` on failure."]
+    pub fn pv_cobra_process(
+        object: *mut pv_cobra_t,
+        pcm: *const i16,
+        is_voiced: *mut f32,
+    ) -> pv_status_t;
+}
+unsafe extern "C" {
+    #[doc = " Getter for number of audio samples per frame.\n\n @return Frame length."]
+    pub fn pv_cobra_frame_length() -> i32;
+}
+unsafe extern "C" {
+    #[doc = " Getter for version.\n\n @return Version."]
+    pub fn pv_cobra_version() -> *const ::std::os::raw::c_char;
+}
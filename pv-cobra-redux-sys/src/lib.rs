@@ -0,0 +1,28 @@
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+
+// The Cobra API only uses fixed-width types, so the same bindings serve every
+// platform.
+#[cfg(not(feature = "regenerate-bindings"))]
+include!("bindings.rs");
+#[cfg(feature = "regenerate-bindings")]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+/// The library linked at build time, as a path under the `lib` directory of
+/// Cobra's repository, or its file name if it came from `PV_COBRA_LIB_PATH`.
+/// `None` if it was found by pkg-config, or isn't linked.
+pub const LINKED_LIB: Option<&str> = option_env!("COBRA_LINKED_LIB");
+
+/// SHA-256 of the library linked at build time, where [`LINKED_LIB`] is known.
+pub const LINKED_LIB_SHA256: Option<&str> = option_env!("COBRA_LINKED_LIB_SHA256");
+
+/// The library's contents, for the dependent crate to load at runtime.
+#[cfg(feature = "embed-lib")]
+pub static EMBEDDED_LIB: &[u8] = include_bytes!(env!("COBRA_EMBEDDED_LIB"));
+
+/// Whether the library is linked. It isn't for docs-only builds, or no-lib
+/// builds for targets Cobra doesn't support, which use stubs instead.
+pub const LIB_AVAILABLE: bool = !cfg!(any(feature = "docs-only", cobra_no_lib));
+
+#[cfg(any(feature = "docs-only", cobra_no_lib))]
+mod stubs;
@@ -0,0 +1,63 @@
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::{pv_cobra_t, pv_status_t, pv_status_t_PV_STATUS_INVALID_STATE};
+
+// Report Cobra's real sample rate and frame length, so that code computing
+// durations still behaves, but refuse to create an instance.
+
+#[unsafe(no_mangle)]
+extern "C" fn pv_sample_rate() -> i32 {
+    16000
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn pv_status_to_string(_status: pv_status_t) -> *const c_char {
+    c"Cobra is unavailable in this build".as_ptr()
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pv_get_error_stack(
+    message_stack: *mut *mut *mut c_char,
+    message_stack_depth: *mut i32,
+) -> pv_status_t {
+    unsafe {
+        *message_stack = ptr::null_mut();
+        *message_stack_depth = 0;
+    }
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn pv_free_error_stack(_message_stack: *mut *mut c_char) {}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn pv_cobra_init(
+    _access_key: *const c_char,
+    object: *mut *mut pv_cobra_t,
+) -> pv_status_t {
+    unsafe { *object = ptr::null_mut() };
+    pv_status_t_PV_STATUS_INVALID_STATE
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn pv_cobra_delete(_object: *mut pv_cobra_t) {}
+
+#[unsafe(no_mangle)]
+extern "C" fn pv_cobra_process(
+    _object: *mut pv_cobra_t,
+    _pcm: *const i16,
+    _is_voiced: *mut f32,
+) -> pv_status_t {
+    pv_status_t_PV_STATUS_INVALID_STATE
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn pv_cobra_frame_length() -> i32 {
+    512
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn pv_cobra_version() -> *const c_char {
+    c"0.0.0-docs-only".as_ptr()
+}
@@ -0,0 +1,119 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::{Error, Result};
+
+/// A Picovoice access key. `Debug` and `Display` never reveal the secret, so
+/// keys can be passed around without ending up in logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AccessKey(String);
+
+impl AccessKey {
+    pub fn new<S: Into<String>>(key: S) -> Self {
+        AccessKey(key.into())
+    }
+
+    /// Reads the key from the given environment variable, e.g.
+    /// `PICOVOICE_ACCESS_KEY`.
+    pub fn from_env(name: &str) -> Result<Self> {
+        let key = env::var(name).map_err(|source| Error::Env {
+            name: name.to_string(),
+            source,
+        })?;
+        Ok(AccessKey(key.trim().to_string()))
+    }
+
+    /// Reads the key from a file, ignoring surrounding whitespace.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let key = fs::read_to_string(path)?;
+        Ok(AccessKey(key.trim().to_string()))
+    }
+
+    /// Returns the secret itself.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for AccessKey {
+    fn from(key: String) -> Self {
+        AccessKey(key)
+    }
+}
+
+impl From<&str> for AccessKey {
+    fn from(key: &str) -> Self {
+        AccessKey(key.to_string())
+    }
+}
+
+impl From<&String> for AccessKey {
+    fn from(key: &String) -> Self {
+        AccessKey(key.clone())
+    }
+}
+
+/// Bytes that aren't valid UTF-8, which no access key contains, are replaced
+/// with U+FFFD, leaving the engine to reject the key. An interior null byte
+/// fails with [`Error::NullValue`] when the key is used, as it does in a
+/// `String`.
+impl From<Vec<u8>> for AccessKey {
+    fn from(key: Vec<u8>) -> Self {
+        let key = String::from_utf8(key)
+            .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned());
+        AccessKey(key)
+    }
+}
+
+impl fmt::Debug for AccessKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AccessKey").field(&"<redacted>").finish()
+    }
+}
+
+impl fmt::Display for AccessKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_access_key_is_redacted() {
+        let key = AccessKey::from("super-secret");
+        assert_eq!(format!("{:?}", key), "AccessKey(\"<redacted>\")");
+        assert_eq!(key.to_string(), "<redacted>");
+        assert_eq!(key.expose(), "super-secret");
+    }
+
+    #[test]
+    fn check_access_key_from_file() {
+        let path = env::temp_dir().join(format!("pv_cobra_redux_key_{}", std::process::id()));
+        fs::write(&path, "file-secret\n").unwrap();
+        let key = AccessKey::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(key.unwrap().expose(), "file-secret");
+    }
+
+    #[test]
+    fn check_access_key_from_missing_env() {
+        let err = AccessKey::from_env("PV_COBRA_REDUX_SURELY_UNSET").unwrap_err();
+        assert!(matches!(err, Error::Env { .. }));
+    }
+
+    #[test]
+    fn check_access_key_from_bytes() {
+        assert_eq!(
+            AccessKey::from(b"byte-secret".to_vec()).expose(),
+            "byte-secret"
+        );
+        assert_eq!(AccessKey::from(vec![b'k', 0xff]).expose(), "k\u{fffd}");
+        let err = crate::Cobra::new(b"null\0secret".to_vec()).unwrap_err();
+        assert!(matches!(err, Error::NullValue));
+    }
+}
@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use crate::detector::HysteresisDetector;
+use crate::{Confidence, duration_to_frames};
+
+const DEFAULT_ENTER_MARGIN: f32 = 0.3;
+const DEFAULT_EXIT_MARGIN: f32 = 0.15;
+const DEFAULT_ADAPTATION: Duration = Duration::from_secs(30);
+
+/// A [`HysteresisDetector`] whose thresholds are derived from the ambient
+/// noise instead of being fixed.
+///
+/// The first frames (the calibration period) are assumed not to contain
+/// speech; their confidences establish a noise floor, and the enter/exit
+/// thresholds are placed a margin above it. Afterwards, the floor keeps
+/// tracking frames classified as silence, slowly, so the thresholds follow
+/// changes in the environment.
+#[derive(Debug, Clone)]
+pub struct AdaptiveDetector {
+    calibration_frames: u64,
+    adaptation_frames: u64,
+    enter_margin: f32,
+    exit_margin: f32,
+    observed: u64,
+    mean: f64,
+    variance: f64,
+    detector: Option<HysteresisDetector>,
+}
+
+impl AdaptiveDetector {
+    pub fn new(calibration: Duration) -> Self {
+        AdaptiveDetector {
+            calibration_frames: duration_to_frames(calibration).max(1),
+            adaptation_frames: duration_to_frames(DEFAULT_ADAPTATION),
+            enter_margin: DEFAULT_ENTER_MARGIN,
+            exit_margin: DEFAULT_EXIT_MARGIN,
+            observed: 0,
+            mean: 0.0,
+            variance: 0.0,
+            detector: None,
+        }
+    }
+
+    /// Distances of the enter and exit thresholds above the noise floor.
+    pub fn with_margins(mut self, enter_margin: f32, exit_margin: f32) -> Self {
+        self.enter_margin = enter_margin;
+        self.exit_margin = exit_margin.min(enter_margin);
+        self
+    }
+
+    /// Time constant with which the noise floor follows silence after
+    /// calibration. Longer values adapt more slowly.
+    pub fn with_adaptation(mut self, time_constant: Duration) -> Self {
+        self.adaptation_frames = duration_to_frames(time_constant).max(1);
+        self
+    }
+
+    /// Feeds the confidence of the next frame and returns whether it is
+    /// considered speech. Always `false` during calibration.
+    pub fn update(&mut self, confidence: Confidence) -> bool {
+        let value = confidence.get() as f64;
+        let Some(detector) = &mut self.detector else {
+            // Welford's online mean and variance over the calibration period.
+            self.observed += 1;
+            let delta = value - self.mean;
+            self.mean += delta / self.observed as f64;
+            self.variance += (delta * (value - self.mean) - self.variance) / self.observed as f64;
+            if self.observed >= self.calibration_frames {
+                let (enter, exit) = self.derive_thresholds();
+                self.detector = Some(HysteresisDetector::new(enter, exit));
+            }
+            return false;
+        };
+        let speech = detector.update(confidence);
+        if !speech {
+            let alpha = 1.0 / self.adaptation_frames as f64;
+            let delta = value - self.mean;
+            self.mean += alpha * delta;
+            self.variance = (1.0 - alpha) * (self.variance + alpha * delta * delta);
+            let (enter, exit) = self.derive_thresholds();
+            if let Some(detector) = &mut self.detector {
+                detector.set_thresholds(enter, exit);
+            }
+        }
+        speech
+    }
+
+    fn derive_thresholds(&self) -> (f32, f32) {
+        let floor = (self.mean + 2.0 * self.variance.max(0.0).sqrt()) as f32;
+        let enter = (floor + self.enter_margin).clamp(0.05, 0.95);
+        let exit = (floor + self.exit_margin).clamp(0.0, enter);
+        (enter, exit)
+    }
+
+    /// Whether the calibration period is over.
+    pub fn is_calibrated(&self) -> bool {
+        self.detector.is_some()
+    }
+
+    /// The current `(enter, exit)` thresholds, once calibrated.
+    pub fn thresholds(&self) -> Option<(f32, f32)> {
+        self.detector
+            .as_ref()
+            .map(|d| (d.enter_threshold(), d.exit_threshold()))
+    }
+
+    pub fn is_speech(&self) -> bool {
+        self.detector.as_ref().is_some_and(|d| d.is_speech())
+    }
+
+    /// Discards the calibration and starts over.
+    pub fn reset(&mut self) {
+        self.observed = 0;
+        self.mean = 0.0;
+        self.variance = 0.0;
+        self.detector = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frames_to_duration;
+
+    #[test]
+    fn check_calibration_derives_thresholds() {
+        let mut detector = AdaptiveDetector::new(frames_to_duration(4));
+        for c in [0.1, 0.1, 0.1, 0.1] {
+            assert!(!detector.update(Confidence::new(c)));
+        }
+        let (enter, exit) = detector.thresholds().unwrap();
+        assert!((enter - 0.4).abs() < 1e-6);
+        assert!((exit - 0.25).abs() < 1e-6);
+        assert!(detector.update(Confidence::new(0.5)));
+        assert!(detector.update(Confidence::new(0.3)));
+        assert!(!detector.update(Confidence::new(0.2)));
+    }
+
+    #[test]
+    fn check_thresholds_follow_noise_floor() {
+        let mut detector =
+            AdaptiveDetector::new(frames_to_duration(2)).with_adaptation(frames_to_duration(4));
+        detector.update(Confidence::new(0.0));
+        detector.update(Confidence::new(0.0));
+        let (quiet, _) = detector.thresholds().unwrap();
+        for _ in 0..50 {
+            detector.update(Confidence::new(0.2));
+        }
+        let (noisy, _) = detector.thresholds().unwrap();
+        assert!(noisy > quiet + 0.15);
+    }
+}
@@ -0,0 +1,255 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::downmix::{Downmix, downmix_into};
+use crate::sample::decode_bytes;
+use crate::{
+    Cobra, Confidence, Endianness, Error, Padding, Result, SampleWidth, frames_to_duration,
+};
+
+/// The layout of the sound data in an AIFF or AIFF-C file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Header {
+    channels: usize,
+    sample_frames: u64,
+    bits: u16,
+    sample_rate: f64,
+    endianness: Endianness,
+    /// Offset of the first sample within the file.
+    data_start: u64,
+}
+
+impl Header {
+    fn bytes_per_sample(&self) -> usize {
+        (self.bits as usize).div_ceil(8)
+    }
+}
+
+/// Runs the AIFF or AIFF-C file at `path` through `cobra`, returning the
+/// start time and confidence of every frame. Supports uncompressed 8, 16
+/// and 24-bit samples, including the little-endian `sowt` variant written by
+/// macOS. Multi-channel audio is averaged down to mono, and a trailing
+/// partial frame is zero-padded. The file must already be at Cobra's sample
+/// rate.
+pub fn process_file<P: AsRef<Path>>(
+    path: P,
+    cobra: &mut Cobra,
+) -> Result<Vec<(Duration, Confidence)>> {
+    process(BufReader::new(File::open(path)?), cobra)
+}
+
+/// Like [`process_file`], but reads the AIFF data from `reader`.
+pub fn process<R: Read + Seek>(
+    mut reader: R,
+    cobra: &mut Cobra,
+) -> Result<Vec<(Duration, Confidence)>> {
+    let header = read_header(&mut reader)?;
+    if header.sample_rate != cobra.sample_rate() as f64 {
+        return Err(unsupported(format!(
+            "sample rate is {} Hz, expected {} Hz",
+            header.sample_rate,
+            cobra.sample_rate()
+        )));
+    }
+    reader.seek(SeekFrom::Start(header.data_start))?;
+
+    let frame_length = cobra.frame_length() as usize;
+    let sample_bytes = header.bytes_per_sample();
+    let block = frame_length * header.channels * sample_bytes;
+    let mut remaining = header.sample_frames * (header.channels * sample_bytes) as u64;
+    let mut bytes = vec![0; block];
+    let mut interleaved = Vec::with_capacity(frame_length * header.channels);
+    let mut frame = Vec::with_capacity(frame_length);
+    let mut confidences = Vec::new();
+    while remaining > 0 {
+        let len = (block as u64).min(remaining) as usize;
+        reader.read_exact(&mut bytes[..len])?;
+        remaining -= len as u64;
+
+        interleaved.clear();
+        match header.bits {
+            1..=8 => interleaved.extend(bytes[..len].iter().map(|&b| (b as i8 as i16) << 8)),
+            9..=16 => decode_bytes(
+                &bytes[..len],
+                SampleWidth::I16,
+                header.endianness,
+                &mut interleaved,
+            ),
+            _ => decode_bytes(
+                &bytes[..len],
+                SampleWidth::I24,
+                header.endianness,
+                &mut interleaved,
+            ),
+        }
+        frame.clear();
+        downmix_into(&interleaved, header.channels, Downmix::Average, &mut frame);
+        if frame.is_empty() {
+            break;
+        }
+        let at = frames_to_duration(confidences.len() as u64);
+        let (confidence, _) = cobra.process_partial(&frame, Padding::Zero)?;
+        confidences.push((at, confidence));
+    }
+    Ok(confidences)
+}
+
+fn read_header<R: Read + Seek>(reader: &mut R) -> Result<Header> {
+    let mut id = [0; 4];
+    reader.read_exact(&mut id)?;
+    if &id != b"FORM" {
+        return Err(unsupported("not an AIFF file"));
+    }
+    read_u32(reader)?;
+    reader.read_exact(&mut id)?;
+    let compressed = match &id {
+        b"AIFF" => false,
+        b"AIFC" => true,
+        _ => return Err(unsupported("not an AIFF file")),
+    };
+
+    let mut comm = None;
+    let mut data_start = None;
+    // Chunks may come in any order, so keep scanning until both the format
+    // and the sound data have been found.
+    while comm.is_none() || data_start.is_none() {
+        if reader.read(&mut id[..1])? == 0 {
+            break;
+        }
+        reader.read_exact(&mut id[1..])?;
+        let size = read_u32(reader)? as u64;
+        let start = reader.stream_position()?;
+        match &id {
+            b"COMM" => comm = Some(read_comm(reader, compressed)?),
+            b"SSND" => {
+                let offset = read_u32(reader)? as u64;
+                data_start = Some(start + 8 + offset);
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length.
+        reader.seek(SeekFrom::Start(start + size + (size & 1)))?;
+    }
+    let (channels, sample_frames, bits, sample_rate, endianness) =
+        comm.ok_or_else(|| unsupported("missing COMM chunk"))?;
+    Ok(Header {
+        channels,
+        sample_frames,
+        bits,
+        sample_rate,
+        endianness,
+        data_start: data_start.ok_or_else(|| unsupported("missing SSND chunk"))?,
+    })
+}
+
+type Comm = (usize, u64, u16, f64, Endianness);
+
+fn read_comm<R: Read>(reader: &mut R, compressed: bool) -> Result<Comm> {
+    let channels = read_u16(reader)? as usize;
+    let sample_frames = read_u32(reader)? as u64;
+    let bits = read_u16(reader)?;
+    let mut rate = [0; 10];
+    reader.read_exact(&mut rate)?;
+    let mut endianness = Endianness::Big;
+    if compressed {
+        let mut compression = [0; 4];
+        reader.read_exact(&mut compression)?;
+        endianness = match &compression {
+            b"NONE" | b"twos" => Endianness::Big,
+            b"sowt" => Endianness::Little,
+            other => {
+                return Err(unsupported(format!(
+                    "`{}` compression",
+                    String::from_utf8_lossy(other)
+                )));
+            }
+        };
+    }
+    if channels == 0 {
+        return Err(unsupported("no channels"));
+    }
+    if !(1..=24).contains(&bits) {
+        return Err(unsupported(format!("{}-bit samples", bits)));
+    }
+    Ok((
+        channels,
+        sample_frames,
+        bits,
+        extended_to_f64(rate),
+        endianness,
+    ))
+}
+
+/// Converts an 80-bit IEEE 754 extended precision number, as used for the
+/// sample rate.
+fn extended_to_f64(bytes: [u8; 10]) -> f64 {
+    let sign = if bytes[0] & 0x80 != 0 { -1.0 } else { 1.0 };
+    let exponent = (u16::from_be_bytes([bytes[0], bytes[1]]) & 0x7fff) as i32;
+    let mut mantissa = [0; 8];
+    mantissa.copy_from_slice(&bytes[2..]);
+    let mantissa = u64::from_be_bytes(mantissa);
+    if exponent == 0 && mantissa == 0 {
+        return 0.0;
+    }
+    sign * mantissa as f64 * 2f64.powi(exponent - 16383 - 63)
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> Result<u16> {
+    let mut buf = [0; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_be_bytes(buf))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+fn unsupported<S: Into<String>>(reason: S) -> Error {
+    Error::UnsupportedFormat {
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const RATE_16000: [u8; 10] = [0x40, 0x0c, 0xfa, 0, 0, 0, 0, 0, 0, 0];
+
+    #[test]
+    fn check_extended_to_f64() {
+        assert_eq!(extended_to_f64(RATE_16000), 16000.0);
+        assert_eq!(
+            extended_to_f64([0x40, 0x0e, 0xac, 0x44, 0, 0, 0, 0, 0, 0]),
+            44100.0
+        );
+    }
+
+    #[test]
+    fn check_read_header() {
+        let mut data = b"FORM\0\0\0\0AIFC".to_vec();
+        data.extend(b"FVER\0\0\0\x04\xa2\x80\x51\x40");
+        data.extend(b"SSND\0\0\0\x0c\0\0\0\0\0\0\0\0\x01\x02\x03\x04");
+        data.extend(b"COMM\0\0\0\x16\0\x02\0\0\0\x01\0\x10");
+        data.extend(RATE_16000);
+        data.extend(b"sowt");
+        let header = read_header(&mut Cursor::new(data)).unwrap();
+        assert_eq!(
+            header,
+            Header {
+                channels: 2,
+                sample_frames: 1,
+                bits: 16,
+                sample_rate: 16000.0,
+                endianness: Endianness::Little,
+                data_start: 40,
+            }
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use blocking::Task;
+use futures_core::Stream;
+use futures_lite::future;
+
+use crate::{Cobra, CobraStream, Confidence, Padding, Result, lock};
+
+/// A handle to a [`Cobra`] whose processing runs on a shared blocking thread
+/// pool rather than the calling task, so it works with any executor, e.g.
+/// async-std or smol. With the `tokio` feature, `tokio::AsyncCobra` uses
+/// Tokio's own blocking pool instead. Cloning the handle
+/// shares the same instance; concurrent calls are serialized, but in no
+/// particular order.
+#[derive(Debug, Clone)]
+pub struct AsyncCobra {
+    cobra: Arc<Mutex<Cobra>>,
+    frame_length: usize,
+}
+
+impl AsyncCobra {
+    pub fn new(cobra: Cobra) -> Self {
+        let frame_length = cobra.frame_length() as usize;
+        AsyncCobra {
+            cobra: Arc::new(Mutex::new(cobra)),
+            frame_length,
+        }
+    }
+
+    pub fn frame_length(&self) -> usize {
+        self.frame_length
+    }
+
+    /// Processes a frame of exactly `frame_length()` samples off the
+    /// executor's threads.
+    pub async fn process(&self, frame: &[i16]) -> Result<Confidence> {
+        let cobra = Arc::clone(&self.cobra);
+        let frame = frame.to_vec();
+        blocking::unblock(move || {
+            let mut cobra = lock(&cobra);
+            cobra.process(&frame)
+        })
+        .await
+    }
+}
+
+/// A chunk being processed on the blocking thread pool, which hands the
+/// stream back along with the result.
+type Pushing = Task<(CobraStream, Result<Vec<Confidence>>)>;
+
+/// Runs the audio chunks of an async stream through a [`CobraStream`],
+/// yielding the confidence of every frame. Chunks may be of any length. Once
+/// the input ends, any buffered audio is processed as a zero-padded final
+/// frame. Like [`AsyncCobra`], processing happens off the executor's threads.
+pub struct ConfidenceStream<S> {
+    input: Option<S>,
+    // Taken while a chunk is being processed.
+    stream: Option<CobraStream>,
+    pushing: Option<Pushing>,
+    ready: VecDeque<Confidence>,
+}
+
+impl<S> ConfidenceStream<S> {
+    pub fn new(input: S, stream: CobraStream) -> Self {
+        ConfidenceStream {
+            input: Some(input),
+            stream: Some(stream),
+            pushing: None,
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Returns the stream, first blocking until any chunk still being
+    /// processed is done.
+    pub fn into_inner(self) -> CobraStream {
+        match self.pushing {
+            Some(pushing) => future::block_on(pushing).0,
+            None => self.stream.unwrap(),
+        }
+    }
+}
+
+impl<S, T> Stream for ConfidenceStream<S>
+where
+    S: Stream<Item = T> + Unpin,
+    T: AsRef<[i16]>,
+{
+    type Item = Result<Confidence>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(confidence) = this.ready.pop_front() {
+                return Poll::Ready(Some(Ok(confidence)));
+            }
+            if let Some(pushing) = &mut this.pushing {
+                let (stream, confidences) = match Pin::new(pushing).poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(pushed) => pushed,
+                };
+                this.pushing = None;
+                this.stream = Some(stream);
+                match confidences {
+                    Ok(confidences) => this.ready.extend(confidences),
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                }
+                continue;
+            }
+            let Some(input) = &mut this.input else {
+                return Poll::Ready(None);
+            };
+            let chunk = match Pin::new(input).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(chunk)) => Some(chunk.as_ref().to_vec()),
+                Poll::Ready(None) => {
+                    this.input = None;
+                    None
+                }
+            };
+            let mut stream = this.stream.take().unwrap();
+            this.pushing = Some(blocking::unblock(move || {
+                let confidences = match chunk {
+                    Some(chunk) => stream.push(&chunk),
+                    None => stream.finish(Padding::Zero),
+                };
+                (stream, confidences)
+            }));
+        }
+    }
+}
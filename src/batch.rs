@@ -0,0 +1,71 @@
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rayon::prelude::*;
+use rayon::{ThreadPoolBuilder, current_thread_index};
+
+use crate::downmix::Downmix;
+use crate::{AccessKey, CancellationToken, Cobra, Confidence, Error, Result, lock, wav};
+
+/// The start time and confidence of every frame of a file.
+pub type FileConfidences = Vec<(Duration, Confidence)>;
+
+/// Runs the WAV files at `paths` through Cobra on `parallelism` threads,
+/// each with its own instance, e.g. to analyze a dataset. The results are
+/// in the same order as `paths`, with each as per [`wav::process_file`].
+///
+/// Fails as a whole only if the instances cannot be created; a file that
+/// cannot be processed gets an error of its own.
+pub fn process_files<K, P>(
+    access_key: K,
+    paths: &[P],
+    parallelism: usize,
+) -> Result<Vec<Result<FileConfidences>>>
+where
+    K: Into<AccessKey>,
+    P: AsRef<Path> + Sync,
+{
+    process_files_cancellable(access_key, paths, parallelism, &CancellationToken::new())
+}
+
+/// Like [`process_files`], but stops early once `token` is cancelled. Files
+/// being processed at that point get the confidences computed so far, and
+/// those not yet started get [`Error::Cancelled`].
+pub fn process_files_cancellable<K, P>(
+    access_key: K,
+    paths: &[P],
+    parallelism: usize,
+    token: &CancellationToken,
+) -> Result<Vec<Result<FileConfidences>>>
+where
+    K: Into<AccessKey>,
+    P: AsRef<Path> + Sync,
+{
+    assert!(parallelism > 0, "parallelism must be non-zero");
+    let access_key = access_key.into();
+    let cobras = (0..parallelism)
+        .map(|_| Cobra::new(access_key.clone()).map(Mutex::new))
+        .collect::<Result<Vec<_>>>()?;
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(parallelism)
+        .thread_name(|i| format!("cobra-batch-{}", i))
+        .build()
+        .map_err(io::Error::other)?;
+    Ok(pool.install(|| {
+        paths
+            .par_iter()
+            .map(|path| {
+                if token.is_cancelled() {
+                    return Err(Error::Cancelled);
+                }
+                // Each thread of the pool only ever uses its own instance, so
+                // the lock is never contended.
+                let index = current_thread_index().expect("running outside the batch pool");
+                let mut cobra = lock(&cobras[index]);
+                wav::process_file_cancellable(path, &mut cobra, Downmix::Average, token)
+            })
+            .collect()
+    }))
+}
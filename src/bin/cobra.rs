@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use pv_cobra_redux::segmenter::SpeechSegmenter;
+use pv_cobra_redux::{AccessKey, Cobra, decode};
+
+#[derive(Parser)]
+#[command(
+    author,
+    version,
+    about = "Voice activity detection for audio files",
+    propagate_version = true
+)]
+struct Cli {
+    /// Picovoice access key. If unspecified, the `PICOVOICE_ACCESS_KEY`
+    /// environment variable is used.
+    #[arg(long, global = true)]
+    access_key: Option<String>,
+
+    /// How results are printed.
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Tab-separated columns.
+    Text,
+    /// One JSON object per line, e.g. `{"t": 1.23, "confidence": 0.87}`.
+    Jsonl,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the start time, in seconds, and speech confidence of every
+    /// frame.
+    Confidences {
+        /// Audio file to analyze, e.g. WAV, FLAC, MP3 or Ogg Vorbis.
+        file: PathBuf,
+    },
+    /// Prints the start and end time, in seconds, of every speech segment.
+    Segments {
+        /// Audio file to analyze, e.g. WAV, FLAC, MP3 or Ogg Vorbis.
+        file: PathBuf,
+        /// Confidence from which a frame counts as speech.
+        #[arg(long, default_value_t = 0.5)]
+        threshold: f32,
+        /// Drops segments shorter than this many milliseconds.
+        #[arg(long, default_value_t = 0)]
+        min_speech_ms: u64,
+        /// Merges segments separated by less than this many milliseconds of
+        /// silence.
+        #[arg(long, default_value_t = 0)]
+        max_gap_ms: u64,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let access_key = match cli.access_key {
+        Some(access_key) => AccessKey::new(access_key),
+        None => AccessKey::from_env("PICOVOICE_ACCESS_KEY")
+            .context("missing `--access-key` or environment variable `PICOVOICE_ACCESS_KEY`")?,
+    };
+    let mut cobra = Cobra::new(access_key)?;
+
+    match cli.command {
+        Command::Confidences { file } => {
+            let confidences = decode::process_file(&file, &mut cobra)
+                .with_context(|| format!("failed to process {}", file.display()))?;
+            for (at, confidence) in confidences {
+                let (at, confidence) = (at.as_secs_f64(), confidence.get());
+                match cli.format {
+                    Format::Text => println!("{:.3}\t{:.3}", at, confidence),
+                    Format::Jsonl => {
+                        println!("{{\"t\": {:.3}, \"confidence\": {:.3}}}", at, confidence)
+                    }
+                }
+            }
+        }
+        Command::Segments {
+            file,
+            threshold,
+            min_speech_ms,
+            max_gap_ms,
+        } => {
+            let confidences = decode::process_file(&file, &mut cobra)
+                .with_context(|| format!("failed to process {}", file.display()))?;
+            let mut segmenter = SpeechSegmenter::new(threshold)
+                .with_min_speech_duration(Duration::from_millis(min_speech_ms))
+                .with_gap_bridging(Duration::from_millis(max_gap_ms));
+            let mut segments: Vec<_> = confidences
+                .into_iter()
+                .filter_map(|(_, confidence)| segmenter.push(confidence))
+                .collect();
+            segments.extend(segmenter.finish());
+            for segment in segments {
+                let (start, end) = (segment.start.as_secs_f64(), segment.end.as_secs_f64());
+                match cli.format {
+                    Format::Text => println!("{:.3}\t{:.3}", start, end),
+                    Format::Jsonl => println!("{{\"start\": {:.3}, \"end\": {:.3}}}", start, end),
+                }
+            }
+        }
+    }
+    Ok(())
+}
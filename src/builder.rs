@@ -0,0 +1,98 @@
+use std::collections::hash_map::RandomState;
+use std::ffi::CString;
+use std::hash::{BuildHasher, Hasher};
+use std::thread;
+use std::time::Duration;
+
+use crate::{AccessKey, Cobra, Error, Instant, Result, RetryPolicy};
+
+const THROTTLE_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const THROTTLE_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Configures how a [`Cobra`] instance is created.
+#[derive(Debug, Clone)]
+pub struct CobraBuilder {
+    access_key: AccessKey,
+    retry_policy: Option<RetryPolicy>,
+    throttle_deadline: Option<Duration>,
+}
+
+impl CobraBuilder {
+    pub fn new<K: Into<AccessKey>>(access_key: K) -> Self {
+        CobraBuilder {
+            access_key: access_key.into(),
+            retry_policy: None,
+            throttle_deadline: None,
+        }
+    }
+
+    /// Retries transient failures according to `policy`.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// When activation is throttled, keeps retrying with jittered exponential
+    /// backoff until `deadline` has elapsed. If activation is still throttled
+    /// at that point, [`Error::ActivationTimedOut`] reports how long was spent
+    /// waiting.
+    pub fn retry_throttled(mut self, deadline: Duration) -> Self {
+        self.throttle_deadline = Some(deadline);
+        self
+    }
+
+    pub fn build(self) -> Result<Cobra> {
+        let access_key = CString::new(self.access_key.expose()).map_err(|_err| Error::NullValue)?;
+        let init = || match self.throttle_deadline {
+            Some(deadline) => init_throttled(&access_key, deadline),
+            None => Cobra::init(&access_key),
+        };
+        match &self.retry_policy {
+            Some(policy) => policy.run(init),
+            None => init(),
+        }
+    }
+}
+
+fn init_throttled(access_key: &CString, deadline: Duration) -> Result<Cobra> {
+    let start = Instant::now();
+    let mut backoff = THROTTLE_INITIAL_BACKOFF;
+    loop {
+        match Cobra::init(access_key) {
+            Err(err @ Error::ActivationThrottled { .. }) => {
+                let elapsed = start.elapsed();
+                if elapsed >= deadline {
+                    return Err(Error::ActivationTimedOut {
+                        elapsed,
+                        source: Box::new(err),
+                    });
+                }
+                thread::sleep(jitter(backoff).min(deadline - elapsed));
+                backoff = (backoff * 2).min(THROTTLE_MAX_BACKOFF);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Picks a delay uniformly between half of `backoff` and `backoff`, so that
+/// many clients throttled at once don't retry in lockstep.
+fn jitter(backoff: Duration) -> Duration {
+    let random = RandomState::new().build_hasher().finish();
+    let fraction = 0.5 + (random as f64 / u64::MAX as f64) * 0.5;
+    backoff.mul_f64(fraction)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_jitter_bounds() {
+        let backoff = Duration::from_millis(800);
+        for _ in 0..100 {
+            let delay = jitter(backoff);
+            assert!(delay >= backoff / 2 && delay <= backoff);
+        }
+    }
+}
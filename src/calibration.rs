@@ -0,0 +1,84 @@
+use crate::Confidence;
+
+/// Maps raw confidences to calibrated probabilities, to correct systematic
+/// over- or under-confidence measured on a deployment's own data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    curve: Curve,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Curve {
+    Piecewise(Vec<(f32, f32)>),
+    Platt { a: f32, b: f32 },
+}
+
+impl Calibration {
+    /// Linearly interpolates between `(raw, calibrated)` points, clamping to
+    /// the first and last point outside of their range.
+    pub fn piecewise<P: Into<Vec<(f32, f32)>>>(points: P) -> Self {
+        let mut points = points.into();
+        assert!(
+            !points.is_empty(),
+            "calibration curve needs at least one point"
+        );
+        points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Calibration {
+            curve: Curve::Piecewise(points),
+        }
+    }
+
+    /// Platt scaling, mapping a raw confidence `x` to `1 / (1 + exp(a * x + b))`.
+    pub fn platt(a: f32, b: f32) -> Self {
+        Calibration {
+            curve: Curve::Platt { a, b },
+        }
+    }
+
+    pub fn apply(&self, confidence: Confidence) -> Confidence {
+        let x = confidence.get();
+        let calibrated = match &self.curve {
+            Curve::Piecewise(points) => {
+                let i = points.partition_point(|&(raw, _)| raw < x);
+                if i == 0 {
+                    points[0].1
+                } else if i == points.len() {
+                    points[i - 1].1
+                } else {
+                    let (x0, y0) = points[i - 1];
+                    let (x1, y1) = points[i];
+                    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+                }
+            }
+            Curve::Platt { a, b } => 1.0 / (1.0 + (a * x + b).exp()),
+        };
+        Confidence::new(calibrated.clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn apply(calibration: &Calibration, x: f32) -> f32 {
+        calibration.apply(Confidence::new(x)).get()
+    }
+
+    #[test]
+    fn check_piecewise() {
+        let calibration = Calibration::piecewise([(0.8, 0.9), (0.2, 0.1), (0.5, 0.3)]);
+        assert_eq!(apply(&calibration, 0.0), 0.1);
+        assert_eq!(apply(&calibration, 0.2), 0.1);
+        assert!((apply(&calibration, 0.35) - 0.2).abs() < 1e-6);
+        assert!((apply(&calibration, 0.65) - 0.6).abs() < 1e-6);
+        assert_eq!(apply(&calibration, 1.0), 0.9);
+    }
+
+    #[test]
+    fn check_platt() {
+        let calibration = Calibration::platt(-10.0, 5.0);
+        assert!((apply(&calibration, 0.5) - 0.5).abs() < 1e-6);
+        assert!(apply(&calibration, 0.9) > 0.95);
+        assert!(apply(&calibration, 0.1) < 0.05);
+    }
+}
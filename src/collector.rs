@@ -0,0 +1,110 @@
+use std::mem;
+use std::time::Duration;
+
+use crate::endpoint::{Endpointer, Utterance};
+use crate::preroll::PreRoll;
+use crate::{Confidence, duration_to_frames, duration_to_samples};
+
+/// Buffers audio while an utterance is in progress and hands back the
+/// complete recording once the [`Endpointer`] decides it has finished, e.g.
+/// to feed a speech recognizer.
+#[derive(Debug, Clone)]
+pub struct UtteranceCollector {
+    endpointer: Endpointer,
+    pre_roll: PreRoll,
+    frames: u64,
+    audio: Vec<i16>,
+    pool: Vec<Vec<i16>>,
+}
+
+impl UtteranceCollector {
+    pub fn new(endpointer: Endpointer) -> Self {
+        UtteranceCollector {
+            endpointer,
+            pre_roll: PreRoll::new(0),
+            frames: 0,
+            audio: Vec::new(),
+            pool: Vec::new(),
+        }
+    }
+
+    /// Includes up to `pre_roll` of audio from before the detected onset.
+    pub fn with_pre_roll(mut self, pre_roll: Duration) -> Self {
+        self.pre_roll = PreRoll::new(duration_to_samples(pre_roll) as usize);
+        self
+    }
+
+    /// Feeds the next frame and its confidence. Once an utterance ends,
+    /// returns it along with its audio, from the pre-roll up to the end of the
+    /// last speech frame.
+    pub fn push(&mut self, pcm: &[i16], confidence: Confidence) -> Option<(Utterance, Vec<i16>)> {
+        let was_collecting = self.endpointer.in_utterance();
+        let utterance = self.endpointer.push(confidence);
+        self.frames += 1;
+        if self.endpointer.in_utterance() && !was_collecting {
+            self.audio = self.pool.pop().unwrap_or_default();
+            self.pre_roll.drain_into(&mut self.audio);
+        }
+        if was_collecting || self.endpointer.in_utterance() {
+            self.audio.extend_from_slice(pcm);
+        } else {
+            self.pre_roll.push(pcm);
+        }
+
+        match utterance {
+            Some(utterance) => {
+                let trailing = self.frames - duration_to_frames(utterance.end);
+                let mut audio = mem::take(&mut self.audio);
+                audio.truncate(audio.len() - trailing as usize * pcm.len());
+                Some((utterance, audio))
+            }
+            None => {
+                if was_collecting && !self.endpointer.in_utterance() {
+                    // The utterance was too short and has been discarded.
+                    let audio = mem::take(&mut self.audio);
+                    self.recycle(audio);
+                }
+                None
+            }
+        }
+    }
+
+    /// Returns a buffer handed out by [`push`](Self::push) so its allocation
+    /// can be reused for a later utterance.
+    pub fn recycle(&mut self, mut audio: Vec<i16>) {
+        audio.clear();
+        self.pool.push(audio);
+    }
+
+    pub fn reset(&mut self) {
+        self.endpointer.reset();
+        self.pre_roll.clear();
+        self.frames = 0;
+        let audio = mem::take(&mut self.audio);
+        self.recycle(audio);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{frames_to_duration, samples_to_duration};
+
+    #[test]
+    fn check_collects_utterance_audio() {
+        let endpointer = Endpointer::new(0.5, frames_to_duration(2));
+        let mut collector =
+            UtteranceCollector::new(endpointer).with_pre_roll(samples_to_duration(4));
+        let confidences = [0.1, 0.1, 0.9, 0.9, 0.1, 0.1];
+        let mut collected = Vec::new();
+        for (i, c) in confidences.iter().enumerate() {
+            let pcm = [i as i16; 4];
+            collected.extend(collector.push(&pcm, Confidence::new(*c)));
+        }
+        assert_eq!(collected.len(), 1);
+        let (utterance, audio) = &collected[0];
+        assert_eq!(utterance.start, frames_to_duration(2));
+        assert_eq!(utterance.end, frames_to_duration(4));
+        assert_eq!(audio, &[1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3]);
+    }
+}
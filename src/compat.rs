@@ -0,0 +1,112 @@
+use std::fmt;
+
+use crate::{Error, Result, frame_length, lib_version, sample_rate};
+
+/// Major version of Cobra the bindings were generated for.
+const VERSION_MAJOR: u32 = 2;
+const SAMPLE_RATE: i32 = 16000;
+const FRAME_LENGTH: i32 = 512;
+
+/// How the loaded library differs from the one the bindings were generated
+/// for, see [`verify_compatibility`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Incompatibility {
+    Version { expected_major: u32, found: String },
+    SampleRate { expected: i32, found: i32 },
+    FrameLength { expected: i32, found: i32 },
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Incompatibility::Version {
+                expected_major,
+                found,
+            } => write!(f, "expected version {}.x, found {}", expected_major, found),
+            Incompatibility::SampleRate { expected, found } => {
+                write!(f, "expected a sample rate of {}, found {}", expected, found)
+            }
+            Incompatibility::FrameLength { expected, found } => write!(
+                f,
+                "expected a frame length of {}, found {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+/// Checks that the loaded library is the one the bindings were generated for,
+/// rather than, say, a different major version swapped in on disk. Creating a
+/// [`Cobra`](crate::Cobra) does this too.
+pub fn verify_compatibility() -> Result<()> {
+    #[cfg(feature = "dlopen")]
+    crate::dlopen::ensure_loaded()?;
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    crate::wasm::ensure_loaded()?;
+    check(lib_version(), sample_rate(), frame_length()).map_err(Error::IncompatibleLibrary)
+}
+
+fn check(
+    version: &str,
+    sample_rate: i32,
+    frame_length: i32,
+) -> std::result::Result<(), Incompatibility> {
+    let major = version
+        .split('.')
+        .next()
+        .and_then(|major| major.parse().ok());
+    if major != Some(VERSION_MAJOR) {
+        return Err(Incompatibility::Version {
+            expected_major: VERSION_MAJOR,
+            found: version.to_string(),
+        });
+    }
+    if sample_rate != SAMPLE_RATE {
+        return Err(Incompatibility::SampleRate {
+            expected: SAMPLE_RATE,
+            found: sample_rate,
+        });
+    }
+    if frame_length != FRAME_LENGTH {
+        return Err(Incompatibility::FrameLength {
+            expected: FRAME_LENGTH,
+            found: frame_length,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_incompatibilities() {
+        assert_eq!(check("2.0.1", 16000, 512), Ok(()));
+        assert_eq!(
+            check("3.0.0", 16000, 512),
+            Err(Incompatibility::Version {
+                expected_major: 2,
+                found: "3.0.0".to_string()
+            })
+        );
+        assert!(matches!(
+            check("garbage", 16000, 512),
+            Err(Incompatibility::Version { .. })
+        ));
+        assert_eq!(
+            check("2.0.0", 8000, 512),
+            Err(Incompatibility::SampleRate {
+                expected: 16000,
+                found: 8000
+            })
+        );
+        assert_eq!(
+            check("2.0.0", 16000, 256),
+            Err(Incompatibility::FrameLength {
+                expected: 512,
+                found: 256
+            })
+        );
+    }
+}
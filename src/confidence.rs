@@ -0,0 +1,95 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Probability of voice activity in a frame, in `[0, 1]`.
+///
+/// Comparisons use [`f32::total_cmp`], so confidences can be sorted and used
+/// with `max`/`min` directly.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Confidence(f32);
+
+impl Confidence {
+    pub const fn new(value: f32) -> Self {
+        Confidence(value)
+    }
+
+    pub const fn get(self) -> f32 {
+        self.0
+    }
+
+    /// Whether this frame counts as speech at the given threshold.
+    pub fn is_speech(self, threshold: f32) -> bool {
+        self.0 >= threshold
+    }
+
+    pub fn as_percent(self) -> f32 {
+        self.0 * 100.0
+    }
+}
+
+impl From<f32> for Confidence {
+    fn from(value: f32) -> Self {
+        Confidence(value)
+    }
+}
+
+impl From<Confidence> for f32 {
+    fn from(confidence: Confidence) -> Self {
+        confidence.0
+    }
+}
+
+impl PartialEq for Confidence {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Confidence {}
+
+impl PartialOrd for Confidence {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Confidence {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_confidence_thresholds() {
+        let confidence = Confidence::new(0.75);
+        assert!(confidence.is_speech(0.5));
+        assert!(confidence.is_speech(0.75));
+        assert!(!confidence.is_speech(0.8));
+        assert_eq!(confidence.as_percent(), 75.0);
+    }
+
+    #[test]
+    fn check_confidence_total_order() {
+        let mut confidences = [
+            Confidence::new(0.5),
+            Confidence::new(f32::NAN),
+            Confidence::new(0.1),
+            Confidence::new(0.9),
+        ];
+        confidences.sort();
+        assert_eq!(confidences[0], Confidence::new(0.1));
+        assert_eq!(confidences[2], Confidence::new(0.9));
+        assert!(confidences[3].get().is_nan());
+        assert_eq!(Confidence::new(0.2).max(Confidence::new(0.3)).get(), 0.3);
+    }
+}
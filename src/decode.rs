@@ -0,0 +1,182 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::downmix::{Downmix, downmix_into};
+use crate::resample::{Resampler, RubatoResampler};
+use crate::{CancellationToken, Cobra, Confidence, Error, Padding, Result, frames_to_duration};
+
+impl From<SymphoniaError> for Error {
+    fn from(err: SymphoniaError) -> Self {
+        match err {
+            SymphoniaError::IoError(err) => Error::from(err),
+            err => Error::UnsupportedFormat {
+                reason: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Decodes the audio file at `path`, in any container and codec enabled in
+/// symphonia (MP3, FLAC, Ogg Vorbis, AAC in MP4/M4A, WAV), and runs it
+/// through `cobra`. Returns the start time and confidence of every frame.
+/// The audio is averaged down to mono and resampled to Cobra's sample rate,
+/// and a trailing partial frame is zero-padded.
+pub fn process_file<P: AsRef<Path>>(
+    path: P,
+    cobra: &mut Cobra,
+) -> Result<Vec<(Duration, Confidence)>> {
+    process_file_cancellable(path, cobra, &CancellationToken::new())
+}
+
+/// Like [`process_file`], but stops early once `token` is cancelled,
+/// returning the confidences of the frames processed up to that point.
+pub fn process_file_cancellable<P: AsRef<Path>>(
+    path: P,
+    cobra: &mut Cobra,
+    token: &CancellationToken,
+) -> Result<Vec<(Duration, Confidence)>> {
+    let path = path.as_ref();
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let source = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        source,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| Error::UnsupportedFormat {
+            reason: "no audio track".to_string(),
+        })?;
+    let track_id = track.id;
+    let input_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| Error::UnsupportedFormat {
+            reason: "unknown sample rate".to_string(),
+        })?;
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let native_rate = cobra.sample_rate() as u32;
+    let mut resampler = if input_rate == native_rate {
+        None
+    } else {
+        Some(RubatoResampler::new(input_rate, native_rate)?)
+    };
+    let mut frames = Frames::new(cobra);
+    let mut interleaved: Option<SampleBuffer<i16>> = None;
+    let mut mono = Vec::new();
+    let mut resampled = Vec::new();
+    loop {
+        if token.is_cancelled() {
+            return Ok(frames.confidences);
+        }
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                break;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Corrupt packets are skipped rather than failing the whole file.
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(err) => return Err(err.into()),
+        };
+        let spec = *decoded.spec();
+        let buf = match &mut interleaved {
+            Some(buf) if buf.capacity() >= decoded.capacity() * spec.channels.count() => buf,
+            buf => buf.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+        };
+        buf.copy_interleaved_ref(decoded);
+
+        mono.clear();
+        downmix_into(
+            buf.samples(),
+            spec.channels.count(),
+            Downmix::Average,
+            &mut mono,
+        );
+        match &mut resampler {
+            Some(resampler) => {
+                resampled.clear();
+                resampler.process(&mono, &mut resampled);
+                frames.push(&resampled)?;
+            }
+            None => frames.push(&mono)?,
+        }
+    }
+    if let Some(resampler) = &mut resampler {
+        resampled.clear();
+        resampler.flush(&mut resampled);
+        frames.push(&resampled)?;
+    }
+    frames.finish()
+}
+
+/// Splits mono audio into frames and collects their confidences.
+struct Frames<'a> {
+    cobra: &'a mut Cobra,
+    frame_length: usize,
+    buf: Vec<i16>,
+    confidences: Vec<(Duration, Confidence)>,
+}
+
+impl<'a> Frames<'a> {
+    fn new(cobra: &'a mut Cobra) -> Self {
+        let frame_length = cobra.frame_length() as usize;
+        Frames {
+            cobra,
+            frame_length,
+            buf: Vec::with_capacity(frame_length),
+            confidences: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, pcm: &[i16]) -> Result<()> {
+        for &sample in pcm {
+            self.buf.push(sample);
+            if self.buf.len() == self.frame_length {
+                self.process()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn process(&mut self) -> Result<()> {
+        let at = frames_to_duration(self.confidences.len() as u64);
+        let (confidence, _) = self.cobra.process_partial(&self.buf, Padding::Zero)?;
+        self.confidences.push((at, confidence));
+        self.buf.clear();
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<(Duration, Confidence)>> {
+        if !self.buf.is_empty() {
+            self.process()?;
+        }
+        Ok(self.confidences)
+    }
+}
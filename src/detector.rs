@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use crate::{Confidence, duration_to_frames};
+
+/// Turns raw confidences into speech/silence decisions using separate
+/// thresholds for entering and leaving speech, so that confidences hovering
+/// around a single threshold don't make the decision flap.
+#[derive(Debug, Clone)]
+pub struct HysteresisDetector {
+    enter_threshold: f32,
+    exit_threshold: f32,
+    hangover_frames: u64,
+    hangover_left: u64,
+    speech: bool,
+}
+
+impl HysteresisDetector {
+    /// Speech starts once a confidence reaches `enter_threshold` and ends once
+    /// one drops below `exit_threshold`.
+    ///
+    /// Panics if `exit_threshold` is greater than `enter_threshold`.
+    pub fn new(enter_threshold: f32, exit_threshold: f32) -> Self {
+        let mut detector = HysteresisDetector {
+            enter_threshold,
+            exit_threshold,
+            hangover_frames: 0,
+            hangover_left: 0,
+            speech: false,
+        };
+        detector.set_thresholds(enter_threshold, exit_threshold);
+        detector
+    }
+
+    /// Keeps reporting speech for `hangover` after confidence drops below the
+    /// exit threshold, so brief pauses within words don't end speech. The
+    /// duration is rounded up to whole frames.
+    pub fn with_hangover(mut self, hangover: Duration) -> Self {
+        self.hangover_frames = duration_to_frames(hangover);
+        self
+    }
+
+    pub fn enter_threshold(&self) -> f32 {
+        self.enter_threshold
+    }
+
+    pub fn exit_threshold(&self) -> f32 {
+        self.exit_threshold
+    }
+
+    /// Replaces the thresholds without resetting the current decision.
+    ///
+    /// Panics if `exit_threshold` is greater than `enter_threshold`.
+    pub fn set_thresholds(&mut self, enter_threshold: f32, exit_threshold: f32) {
+        assert!(
+            exit_threshold <= enter_threshold,
+            "exit threshold ({}) must not exceed enter threshold ({})",
+            exit_threshold,
+            enter_threshold
+        );
+        self.enter_threshold = enter_threshold;
+        self.exit_threshold = exit_threshold;
+    }
+
+    /// Feeds the confidence of the next frame and returns whether it is
+    /// considered speech.
+    pub fn update(&mut self, confidence: Confidence) -> bool {
+        let threshold = if self.speech {
+            self.exit_threshold
+        } else {
+            self.enter_threshold
+        };
+        if confidence.is_speech(threshold) {
+            self.speech = true;
+            self.hangover_left = self.hangover_frames;
+        } else if self.speech && self.hangover_left > 0 {
+            self.hangover_left -= 1;
+        } else {
+            self.speech = false;
+        }
+        self.speech
+    }
+
+    pub fn is_speech(&self) -> bool {
+        self.speech
+    }
+
+    pub fn reset(&mut self) {
+        self.speech = false;
+        self.hangover_left = 0;
+    }
+}
+
+/// Coarse label for a single frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameLabel {
+    Speech,
+    Silence,
+    /// The confidence falls between the silence and speech thresholds.
+    Uncertain,
+}
+
+/// Labels frames by comparing their confidence against two threshold bands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameClassifier {
+    speech_threshold: f32,
+    silence_threshold: f32,
+}
+
+impl Default for FrameClassifier {
+    fn default() -> Self {
+        FrameClassifier::new(0.7, 0.3)
+    }
+}
+
+impl FrameClassifier {
+    /// Frames at or above `speech_threshold` are speech and frames below
+    /// `silence_threshold` are silence.
+    ///
+    /// Panics if `silence_threshold` is greater than `speech_threshold`.
+    pub fn new(speech_threshold: f32, silence_threshold: f32) -> Self {
+        assert!(
+            silence_threshold <= speech_threshold,
+            "silence threshold ({}) must not exceed speech threshold ({})",
+            silence_threshold,
+            speech_threshold
+        );
+        FrameClassifier {
+            speech_threshold,
+            silence_threshold,
+        }
+    }
+
+    pub fn classify(&self, confidence: Confidence) -> FrameLabel {
+        if confidence.is_speech(self.speech_threshold) {
+            FrameLabel::Speech
+        } else if confidence.is_speech(self.silence_threshold) {
+            FrameLabel::Uncertain
+        } else {
+            FrameLabel::Silence
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_hysteresis() {
+        let mut detector = HysteresisDetector::new(0.7, 0.3);
+        let decisions: Vec<bool> = [0.5, 0.8, 0.5, 0.3, 0.2, 0.5, 0.7]
+            .iter()
+            .map(|&c| detector.update(Confidence::new(c)))
+            .collect();
+        assert_eq!(decisions, [false, true, true, true, false, false, true]);
+    }
+
+    #[test]
+    fn check_hangover() {
+        let mut detector =
+            HysteresisDetector::new(0.5, 0.5).with_hangover(crate::frames_to_duration(2));
+        let decisions: Vec<bool> = [0.9, 0.1, 0.1, 0.9, 0.1, 0.1, 0.1, 0.1]
+            .iter()
+            .map(|&c| detector.update(Confidence::new(c)))
+            .collect();
+        assert_eq!(
+            decisions,
+            [true, true, true, true, true, true, false, false]
+        );
+    }
+
+    #[test]
+    fn check_frame_classifier() {
+        let classifier = FrameClassifier::new(0.6, 0.4);
+        assert_eq!(
+            classifier.classify(Confidence::new(0.6)),
+            FrameLabel::Speech
+        );
+        assert_eq!(
+            classifier.classify(Confidence::new(0.5)),
+            FrameLabel::Uncertain
+        );
+        assert_eq!(
+            classifier.classify(Confidence::new(0.4)),
+            FrameLabel::Uncertain
+        );
+        assert_eq!(
+            classifier.classify(Confidence::new(0.1)),
+            FrameLabel::Silence
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_inverted_thresholds_panic() {
+        HysteresisDetector::new(0.3, 0.7);
+    }
+}
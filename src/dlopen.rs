@@ -0,0 +1,206 @@
+use std::ffi::OsStr;
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use libloading::Library;
+
+pub(crate) use pv_cobra_redux_sys::{
+    pv_cobra, pv_status_t, pv_status_t_PV_STATUS_ACTIVATION_ERROR,
+    pv_status_t_PV_STATUS_ACTIVATION_LIMIT_REACHED, pv_status_t_PV_STATUS_ACTIVATION_REFUSED,
+    pv_status_t_PV_STATUS_ACTIVATION_THROTTLED, pv_status_t_PV_STATUS_INVALID_ARGUMENT,
+    pv_status_t_PV_STATUS_INVALID_STATE, pv_status_t_PV_STATUS_IO_ERROR,
+    pv_status_t_PV_STATUS_KEY_ERROR, pv_status_t_PV_STATUS_OUT_OF_MEMORY,
+    pv_status_t_PV_STATUS_RUNTIME_ERROR, pv_status_t_PV_STATUS_STOP_ITERATION,
+};
+
+use crate::{Error, Result, lock};
+
+/// Name the library is looked up by, through the platform's usual search path,
+/// when it's used before [`load_library`] is called. With the `embed-lib`
+/// feature, the embedded library is extracted under this name instead.
+#[cfg(target_os = "windows")]
+const DEFAULT_LIBRARY: &str = "libpv_cobra.dll";
+#[cfg(target_vendor = "apple")]
+const DEFAULT_LIBRARY: &str = "libpv_cobra.dylib";
+#[cfg(not(any(target_os = "windows", target_vendor = "apple")))]
+const DEFAULT_LIBRARY: &str = "libpv_cobra.so";
+
+/// The engine's entry points, resolved from a loaded library.
+pub(crate) struct Api {
+    path: PathBuf,
+    sample_rate: unsafe extern "C" fn() -> i32,
+    get_error_stack: unsafe extern "C" fn(*mut *mut *mut c_char, *mut i32) -> pv_status_t,
+    free_error_stack: unsafe extern "C" fn(*mut *mut c_char),
+    init: unsafe extern "C" fn(*const c_char, *mut *mut pv_cobra) -> pv_status_t,
+    delete: unsafe extern "C" fn(*mut pv_cobra),
+    process: unsafe extern "C" fn(*mut pv_cobra, *const i16, *mut f32) -> pv_status_t,
+    frame_length: unsafe extern "C" fn() -> i32,
+    version: unsafe extern "C" fn() -> *const c_char,
+    // Never unloaded, as the function pointers above point into it.
+    _library: Library,
+}
+
+static API: OnceLock<Api> = OnceLock::new();
+static LOADING: Mutex<()> = Mutex::new(());
+
+/// Loads the Cobra library at `path`, for the `dlopen` feature. This has to
+/// happen before anything calls into the engine, otherwise the library is
+/// loaded by name from the platform's search path, or the embedded one is used
+/// with the `embed-lib` feature. Once loaded, the library stays loaded, and
+/// loading one from a different path fails.
+pub fn load_library<P: AsRef<Path>>(path: P) -> Result<()> {
+    let _loading = lock(&LOADING);
+    load_locked(path.as_ref())
+}
+
+/// Does the work of [`load_library`], with `LOADING` held by the caller.
+fn load_locked(path: &Path) -> Result<()> {
+    if let Some(api) = API.get() {
+        if api.path == path {
+            return Ok(());
+        }
+        return Err(Error::LibraryLoad {
+            path: path.to_path_buf(),
+            reason: format!("already loaded from {}", api.path.display()),
+        });
+    }
+    let api = unsafe { Api::load(path.as_os_str()) }.map_err(|err| Error::LibraryLoad {
+        path: path.to_path_buf(),
+        reason: err.to_string(),
+    })?;
+    let _ = API.set(api);
+    Ok(())
+}
+
+impl Api {
+    unsafe fn load(path: &OsStr) -> std::result::Result<Self, libloading::Error> {
+        unsafe {
+            let library = Library::new(path)?;
+            Ok(Api {
+                path: PathBuf::from(path),
+                sample_rate: *library.get(b"pv_sample_rate\0")?,
+                get_error_stack: *library.get(b"pv_get_error_stack\0")?,
+                free_error_stack: *library.get(b"pv_free_error_stack\0")?,
+                init: *library.get(b"pv_cobra_init\0")?,
+                delete: *library.get(b"pv_cobra_delete\0")?,
+                process: *library.get(b"pv_cobra_process\0")?,
+                frame_length: *library.get(b"pv_cobra_frame_length\0")?,
+                version: *library.get(b"pv_cobra_version\0")?,
+                _library: library,
+            })
+        }
+    }
+}
+
+/// Returns the loaded library, loading the default one if [`load_library`]
+/// hasn't been called yet.
+pub(crate) fn ensure_loaded() -> Result<&'static Api> {
+    if let Some(api) = API.get() {
+        return Ok(api);
+    }
+    let _loading = lock(&LOADING);
+    if let Some(api) = API.get() {
+        return Ok(api);
+    }
+    #[cfg(feature = "embed-lib")]
+    load_locked(&extract_embedded()?)?;
+    #[cfg(not(feature = "embed-lib"))]
+    load_locked(Path::new(DEFAULT_LIBRARY))?;
+    Ok(API.get().unwrap())
+}
+
+fn api() -> &'static Api {
+    ensure_loaded()
+        .unwrap_or_else(|err| panic!("{}; call `load_library` with its location first", err))
+}
+
+/// Writes the embedded library to the cache directory, unless an earlier run
+/// already did, and returns its path. Each build of the library gets a
+/// directory of its own, so upgrading doesn't pick up a stale copy.
+#[cfg(feature = "embed-lib")]
+fn extract_embedded() -> Result<PathBuf> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::{fs, process};
+
+    use pv_cobra_redux_sys::{EMBEDDED_LIB, LINKED_LIB_SHA256};
+
+    let dir = dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("pv_cobra_redux")
+        .join(LINKED_LIB_SHA256.unwrap_or("embedded"));
+    let path = dir.join(DEFAULT_LIBRARY);
+    let extracted = |path: &Path| {
+        fs::metadata(path).is_ok_and(|metadata| metadata.len() == EMBEDDED_LIB.len() as u64)
+    };
+    if extracted(&path) {
+        return Ok(path);
+    }
+    let failed = |err: std::io::Error| Error::LibraryLoad {
+        path: path.clone(),
+        reason: format!("extracting the embedded library failed: {}", err),
+    };
+    fs::create_dir_all(&dir).map_err(failed)?;
+    // Written under another name first, so that a concurrent run never loads
+    // a partial copy.
+    static EXTRACTIONS: AtomicUsize = AtomicUsize::new(0);
+    let partial = dir.join(format!(
+        "{}.{}.{}",
+        DEFAULT_LIBRARY,
+        process::id(),
+        EXTRACTIONS.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&partial, EMBEDDED_LIB).map_err(failed)?;
+    if let Err(err) = fs::rename(&partial, &path) {
+        let _ = fs::remove_file(&partial);
+        // Another process may have put its copy in place first, which is
+        // just as good, e.g. on Windows where the target can't be replaced
+        // while it's loaded.
+        if !extracted(&path) {
+            return Err(failed(err));
+        }
+    }
+    Ok(path)
+}
+
+pub(crate) unsafe fn pv_sample_rate() -> i32 {
+    unsafe { (api().sample_rate)() }
+}
+
+pub(crate) unsafe fn pv_get_error_stack(
+    message_stack: *mut *mut *mut c_char,
+    message_stack_depth: *mut i32,
+) -> pv_status_t {
+    unsafe { (api().get_error_stack)(message_stack, message_stack_depth) }
+}
+
+pub(crate) unsafe fn pv_free_error_stack(message_stack: *mut *mut c_char) {
+    unsafe { (api().free_error_stack)(message_stack) }
+}
+
+pub(crate) unsafe fn pv_cobra_init(
+    access_key: *const c_char,
+    object: *mut *mut pv_cobra,
+) -> pv_status_t {
+    unsafe { (api().init)(access_key, object) }
+}
+
+pub(crate) unsafe fn pv_cobra_delete(object: *mut pv_cobra) {
+    unsafe { (api().delete)(object) }
+}
+
+pub(crate) unsafe fn pv_cobra_process(
+    object: *mut pv_cobra,
+    pcm: *const i16,
+    is_voiced: *mut f32,
+) -> pv_status_t {
+    unsafe { (api().process)(object, pcm, is_voiced) }
+}
+
+pub(crate) unsafe fn pv_cobra_frame_length() -> i32 {
+    unsafe { (api().frame_length)() }
+}
+
+pub(crate) unsafe fn pv_cobra_version() -> *const c_char {
+    unsafe { (api().version)() }
+}
@@ -0,0 +1,86 @@
+/// How [`downmix`] reduces interleaved multi-channel audio to mono.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Downmix {
+    /// The mean of all channels.
+    #[default]
+    Average,
+    /// The first channel.
+    Left,
+    /// The second channel, or the first for mono input.
+    Right,
+    /// Whichever channel has the most energy over the whole buffer passed
+    /// in, e.g. the microphone closest to the speaker.
+    MaxEnergy,
+    /// The channel at the given index.
+    Channel(usize),
+}
+
+/// Converts `interleaved` audio with `channels` channels to mono. Any
+/// trailing incomplete sample frame is ignored.
+pub fn downmix(interleaved: &[i16], channels: usize, strategy: Downmix) -> Vec<i16> {
+    let mut mono = Vec::with_capacity(interleaved.len() / channels.max(1));
+    downmix_into(interleaved, channels, strategy, &mut mono);
+    mono
+}
+
+/// Like [`downmix`], but appends the mono audio to `out`.
+///
+/// # Panics
+///
+/// Panics if `channels` is zero, or if [`Downmix::Channel`] names a channel
+/// that does not exist.
+pub fn downmix_into(interleaved: &[i16], channels: usize, strategy: Downmix, out: &mut Vec<i16>) {
+    assert!(channels > 0, "audio must have at least one channel");
+    let frames = interleaved.chunks_exact(channels);
+    let channel = match strategy {
+        Downmix::Average => {
+            out.extend(frames.map(|frame| {
+                let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+                (sum / channels as i32) as i16
+            }));
+            return;
+        }
+        Downmix::Left => 0,
+        Downmix::Right => 1.min(channels - 1),
+        Downmix::MaxEnergy => (0..channels)
+            .max_by_key(|&channel| {
+                interleaved
+                    .chunks_exact(channels)
+                    .map(|frame| (frame[channel] as i64).pow(2))
+                    .sum::<i64>()
+            })
+            .unwrap_or(0),
+        Downmix::Channel(channel) => {
+            assert!(
+                channel < channels,
+                "channel {} out of range for {} channels",
+                channel,
+                channels
+            );
+            channel
+        }
+    };
+    out.extend(frames.map(|frame| frame[channel]));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_downmix_strategies() {
+        let interleaved = [10, -10, 400, 20, -20, -500, 30, -30, 600, 7];
+        assert_eq!(downmix(&interleaved, 3, Downmix::Average), [133, -166, 200]);
+        assert_eq!(downmix(&interleaved, 3, Downmix::Left), [10, 20, 30]);
+        assert_eq!(downmix(&interleaved, 3, Downmix::Right), [-10, -20, -30]);
+        assert_eq!(
+            downmix(&interleaved, 3, Downmix::MaxEnergy),
+            [400, -500, 600]
+        );
+        assert_eq!(
+            downmix(&interleaved, 3, Downmix::Channel(2)),
+            [400, -500, 600]
+        );
+        assert_eq!(downmix(&[1, 2], 1, Downmix::Right), [1, 2]);
+    }
+}
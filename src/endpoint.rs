@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use crate::detector::HysteresisDetector;
+use crate::{Confidence, duration_to_frames, frames_to_duration};
+
+/// A finished utterance, from the first to the last speech frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Utterance {
+    pub start: Duration,
+    pub end: Duration,
+}
+
+impl Utterance {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Detects the end of an utterance: speech followed by a configurable stretch
+/// of continuous silence. Useful for deciding when a speaker has finished
+/// their turn.
+#[derive(Debug, Clone)]
+pub struct Endpointer {
+    detector: HysteresisDetector,
+    silence_frames: u64,
+    min_utterance_frames: u64,
+    frames: u64,
+    start: Option<u64>,
+    last_speech_end: u64,
+}
+
+impl Endpointer {
+    /// Ends an utterance after `silence` without speech.
+    pub fn new(threshold: f32, silence: Duration) -> Self {
+        Self::with_detector(HysteresisDetector::new(threshold, threshold), silence)
+    }
+
+    pub fn with_detector(detector: HysteresisDetector, silence: Duration) -> Self {
+        Endpointer {
+            detector,
+            silence_frames: duration_to_frames(silence).max(1),
+            min_utterance_frames: 0,
+            frames: 0,
+            start: None,
+            last_speech_end: 0,
+        }
+    }
+
+    /// Ignores utterances with less than `min` of speech, e.g. coughs.
+    pub fn with_min_utterance(mut self, min: Duration) -> Self {
+        self.min_utterance_frames = duration_to_frames(min);
+        self
+    }
+
+    /// Feeds the confidence of the next frame, returning the utterance once
+    /// it is finished.
+    pub fn push(&mut self, confidence: Confidence) -> Option<Utterance> {
+        let frame = self.frames;
+        self.frames += 1;
+        if self.detector.update(confidence) {
+            self.start.get_or_insert(frame);
+            self.last_speech_end = self.frames;
+            return None;
+        }
+        let start = self.start?;
+        if self.frames - self.last_speech_end < self.silence_frames {
+            return None;
+        }
+        self.start = None;
+        if self.last_speech_end - start < self.min_utterance_frames {
+            return None;
+        }
+        Some(Utterance {
+            start: frames_to_duration(start),
+            end: frames_to_duration(self.last_speech_end),
+        })
+    }
+
+    /// Whether an utterance has started and not yet been endpointed.
+    pub fn in_utterance(&self) -> bool {
+        self.start.is_some()
+    }
+
+    pub fn reset(&mut self) {
+        self.detector.reset();
+        self.frames = 0;
+        self.start = None;
+        self.last_speech_end = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn push_all(endpointer: &mut Endpointer, confidences: &[f32]) -> Vec<(usize, Utterance)> {
+        confidences
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &c)| endpointer.push(Confidence::new(c)).map(|u| (i, u)))
+            .collect()
+    }
+
+    #[test]
+    fn check_endpoint_after_silence() {
+        let mut endpointer = Endpointer::new(0.5, frames_to_duration(2));
+        let utterances = push_all(&mut endpointer, &[0.1, 0.9, 0.2, 0.9, 0.2, 0.2, 0.2]);
+        assert_eq!(
+            utterances,
+            [(
+                5,
+                Utterance {
+                    start: frames_to_duration(1),
+                    end: frames_to_duration(4),
+                }
+            )]
+        );
+        assert!(!endpointer.in_utterance());
+    }
+
+    #[test]
+    fn check_short_utterances_are_ignored() {
+        let mut endpointer =
+            Endpointer::new(0.5, frames_to_duration(1)).with_min_utterance(frames_to_duration(2));
+        assert!(push_all(&mut endpointer, &[0.9, 0.1, 0.1]).is_empty());
+        assert_eq!(push_all(&mut endpointer, &[0.9, 0.9, 0.1]).len(), 1);
+    }
+}
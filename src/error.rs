@@ -0,0 +1,338 @@
+use std::env;
+use std::ffi::CStr;
+use std::fmt;
+use std::io;
+use std::os::raw::{c_char, c_uint};
+use std::path::PathBuf;
+use std::ptr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{Incompatibility, ffi};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors returned by the engine carry the messages Cobra reported through
+/// `pv_get_error_stack`, which is often the only hint as to what went wrong
+/// (e.g. why an access key was refused).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    NullValue,
+    OutOfMemory {
+        messages: Vec<String>,
+    },
+    IoError {
+        messages: Vec<String>,
+    },
+    InvalidArgument {
+        messages: Vec<String>,
+    },
+    StopIteration {
+        messages: Vec<String>,
+    },
+    KeyError {
+        messages: Vec<String>,
+    },
+    InvalidState {
+        messages: Vec<String>,
+    },
+    RuntimeError {
+        messages: Vec<String>,
+    },
+    ActivationError {
+        messages: Vec<String>,
+    },
+    ActivationLimitReached {
+        messages: Vec<String>,
+    },
+    ActivationThrottled {
+        messages: Vec<String>,
+    },
+    ActivationRefused {
+        messages: Vec<String>,
+    },
+    InvalidFrameLength {
+        expected: usize,
+        got: usize,
+    },
+    InvalidByteLength {
+        expected: usize,
+        got: usize,
+    },
+    UnknownError {
+        status: c_uint,
+        messages: Vec<String>,
+    },
+    ActivationTimedOut {
+        elapsed: Duration,
+        source: Box<Error>,
+    },
+    UnsupportedFormat {
+        reason: String,
+    },
+    WorkerStopped,
+    AtCapacity {
+        limit: usize,
+    },
+    Cancelled,
+    ShutdownTimedOut {
+        timeout: Duration,
+    },
+    LibraryLoad {
+        path: PathBuf,
+        reason: String,
+    },
+    IncompatibleLibrary(Incompatibility),
+    UnsupportedPlatform,
+    Io(Arc<io::Error>),
+    Env {
+        name: String,
+        source: env::VarError,
+    },
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(Arc::new(err))
+    }
+}
+
+impl From<ffi::pv_status_t> for Error {
+    fn from(status: ffi::pv_status_t) -> Self {
+        Error::with_messages(status, Vec::new())
+    }
+}
+
+impl Error {
+    /// Builds the error for a failed call, draining Cobra's error stack.
+    pub(crate) fn from_status(status: ffi::pv_status_t) -> Self {
+        Error::with_messages(status, error_stack())
+    }
+
+    fn with_messages(status: ffi::pv_status_t, messages: Vec<String>) -> Self {
+        match status {
+            ffi::pv_status_t_PV_STATUS_OUT_OF_MEMORY => Error::OutOfMemory { messages },
+            ffi::pv_status_t_PV_STATUS_IO_ERROR => Error::IoError { messages },
+            ffi::pv_status_t_PV_STATUS_INVALID_ARGUMENT => Error::InvalidArgument { messages },
+            ffi::pv_status_t_PV_STATUS_STOP_ITERATION => Error::StopIteration { messages },
+            ffi::pv_status_t_PV_STATUS_KEY_ERROR => Error::KeyError { messages },
+            ffi::pv_status_t_PV_STATUS_INVALID_STATE => Error::InvalidState { messages },
+            ffi::pv_status_t_PV_STATUS_RUNTIME_ERROR => Error::RuntimeError { messages },
+            ffi::pv_status_t_PV_STATUS_ACTIVATION_ERROR => Error::ActivationError { messages },
+            ffi::pv_status_t_PV_STATUS_ACTIVATION_LIMIT_REACHED => {
+                Error::ActivationLimitReached { messages }
+            }
+            ffi::pv_status_t_PV_STATUS_ACTIVATION_THROTTLED => {
+                Error::ActivationThrottled { messages }
+            }
+            ffi::pv_status_t_PV_STATUS_ACTIVATION_REFUSED => Error::ActivationRefused { messages },
+            _ => Error::UnknownError {
+                status: status as c_uint,
+                messages,
+            },
+        }
+    }
+
+    /// Whether this error was raised while activating the access key.
+    pub fn is_activation(&self) -> bool {
+        matches!(
+            self,
+            Error::ActivationError { .. }
+                | Error::ActivationLimitReached { .. }
+                | Error::ActivationThrottled { .. }
+                | Error::ActivationRefused { .. }
+                | Error::ActivationTimedOut { .. }
+        )
+    }
+
+    /// Whether retrying the same operation later may succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Error::IoError { .. } | Error::ActivationThrottled { .. } | Error::AtCapacity { .. }
+        )
+    }
+
+    /// The `pv_status_t` code this error was created from, if any.
+    pub fn status_code(&self) -> Option<c_uint> {
+        let status = match self {
+            Error::OutOfMemory { .. } => ffi::pv_status_t_PV_STATUS_OUT_OF_MEMORY,
+            Error::IoError { .. } => ffi::pv_status_t_PV_STATUS_IO_ERROR,
+            Error::InvalidArgument { .. } => ffi::pv_status_t_PV_STATUS_INVALID_ARGUMENT,
+            Error::StopIteration { .. } => ffi::pv_status_t_PV_STATUS_STOP_ITERATION,
+            Error::KeyError { .. } => ffi::pv_status_t_PV_STATUS_KEY_ERROR,
+            Error::InvalidState { .. } => ffi::pv_status_t_PV_STATUS_INVALID_STATE,
+            Error::RuntimeError { .. } => ffi::pv_status_t_PV_STATUS_RUNTIME_ERROR,
+            Error::ActivationError { .. } => ffi::pv_status_t_PV_STATUS_ACTIVATION_ERROR,
+            Error::ActivationLimitReached { .. } => {
+                ffi::pv_status_t_PV_STATUS_ACTIVATION_LIMIT_REACHED
+            }
+            Error::ActivationThrottled { .. } => ffi::pv_status_t_PV_STATUS_ACTIVATION_THROTTLED,
+            Error::ActivationRefused { .. } => ffi::pv_status_t_PV_STATUS_ACTIVATION_REFUSED,
+            Error::UnknownError { status, .. } => *status,
+            _ => return None,
+        };
+        Some(status)
+    }
+
+    /// Diagnostic messages reported by Cobra alongside the status code.
+    pub fn messages(&self) -> &[String] {
+        match self {
+            Error::OutOfMemory { messages }
+            | Error::IoError { messages }
+            | Error::InvalidArgument { messages }
+            | Error::StopIteration { messages }
+            | Error::KeyError { messages }
+            | Error::InvalidState { messages }
+            | Error::RuntimeError { messages }
+            | Error::ActivationError { messages }
+            | Error::ActivationLimitReached { messages }
+            | Error::ActivationThrottled { messages }
+            | Error::ActivationRefused { messages }
+            | Error::UnknownError { messages, .. } => messages,
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NullValue => write!(f, "unexpected null value")?,
+            Error::OutOfMemory { .. } => write!(f, "out of memory")?,
+            Error::IoError { .. } => write!(f, "I/O error")?,
+            Error::InvalidArgument { .. } => write!(f, "invalid argument")?,
+            Error::StopIteration { .. } => write!(f, "stop iteration")?,
+            Error::KeyError { .. } => write!(f, "key error")?,
+            Error::InvalidState { .. } => write!(f, "invalid state")?,
+            Error::RuntimeError { .. } => write!(f, "runtime error")?,
+            Error::ActivationError { .. } => write!(f, "activation error")?,
+            Error::ActivationLimitReached { .. } => write!(f, "activation limit reached")?,
+            Error::ActivationThrottled { .. } => write!(f, "activation throttled")?,
+            Error::ActivationRefused { .. } => write!(f, "activation refused")?,
+            Error::InvalidFrameLength { expected, got } => write!(
+                f,
+                "invalid frame length: expected {} samples, got {}",
+                expected, got
+            )?,
+            Error::InvalidByteLength { expected, got } => write!(
+                f,
+                "invalid frame length: expected {} bytes, got {}",
+                expected, got
+            )?,
+            Error::UnknownError { status, .. } => {
+                write!(f, "non-zero status returned: {}", status)?
+            }
+            Error::ActivationTimedOut { elapsed, source } => write!(
+                f,
+                "activation still failing after waiting {:.1}s: {}",
+                elapsed.as_secs_f64(),
+                source
+            )?,
+            Error::UnsupportedFormat { reason } => {
+                write!(f, "unsupported audio format: {}", reason)?
+            }
+            Error::WorkerStopped => write!(f, "processing worker has stopped")?,
+            Error::AtCapacity { limit } => {
+                write!(f, "at capacity: {} instances already in use", limit)?
+            }
+            Error::Cancelled => write!(f, "processing was cancelled")?,
+            Error::ShutdownTimedOut { timeout } => write!(
+                f,
+                "worker still running after waiting {:.1}s to shut down",
+                timeout.as_secs_f64()
+            )?,
+            Error::LibraryLoad { path, reason } => write!(
+                f,
+                "failed to load the Cobra library from {}: {}",
+                path.display(),
+                reason
+            )?,
+            Error::IncompatibleLibrary(incompatibility) => {
+                write!(f, "incompatible Cobra library: {}", incompatibility)?
+            }
+            Error::UnsupportedPlatform => write!(f, "Cobra isn't available on this platform")?,
+            Error::Io(err) => write!(f, "I/O error: {}", err)?,
+            Error::Env { name, source } => {
+                write!(f, "environment variable `{}`: {}", name, source)?
+            }
+        }
+        let messages = self.messages();
+        if !messages.is_empty() {
+            write!(f, ": {}", messages.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ActivationTimedOut { source, .. } => Some(source.as_ref()),
+            Error::Io(err) => Some(err.as_ref()),
+            Error::Env { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn error_stack() -> Vec<String> {
+    let mut message_stack: *mut *mut c_char = ptr::null_mut();
+    let mut depth: i32 = 0;
+    // The status is deliberately ignored: per the Picovoice docs a non-null
+    // stack is valid regardless, and a failure here only affects later calls.
+    let _ = unsafe { ffi::pv_get_error_stack(&mut message_stack, &mut depth) };
+    if message_stack.is_null() {
+        return Vec::new();
+    }
+    let messages = (0..depth.max(0) as usize)
+        .map(|i| {
+            let message = unsafe { *message_stack.add(i) };
+            if message.is_null() {
+                String::new()
+            } else {
+                unsafe { CStr::from_ptr(message) }
+                    .to_string_lossy()
+                    .into_owned()
+            }
+        })
+        .collect();
+    unsafe { ffi::pv_free_error_stack(message_stack) };
+    messages
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_error_status_code_round_trip() {
+        for status in 1..=12 {
+            assert_eq!(Error::from(status).status_code(), Some(status));
+        }
+        assert_eq!(Error::NullValue.status_code(), None);
+    }
+
+    #[test]
+    fn check_error_predicates() {
+        let throttled = Error::from(ffi::pv_status_t_PV_STATUS_ACTIVATION_THROTTLED);
+        assert!(throttled.is_activation());
+        assert!(throttled.is_transient());
+        let refused = Error::from(ffi::pv_status_t_PV_STATUS_ACTIVATION_REFUSED);
+        assert!(refused.is_activation());
+        assert!(!refused.is_transient());
+        let io = Error::from(ffi::pv_status_t_PV_STATUS_IO_ERROR);
+        assert!(io.is_transient());
+        assert!(!io.is_activation());
+    }
+
+    #[test]
+    fn check_error_display_includes_messages() {
+        let err = Error::RuntimeError {
+            messages: vec!["first".to_string(), "second".to_string()],
+        };
+        assert_eq!(err.to_string(), "runtime error: first; second");
+        assert_eq!(Error::NullValue.to_string(), "unexpected null value");
+    }
+}
@@ -0,0 +1,255 @@
+use std::mem;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::detector::HysteresisDetector;
+use crate::{Confidence, duration_to_frames, frames_to_duration};
+
+/// A speech boundary, timed relative to the first frame pushed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    SpeechStart { at: Duration },
+    SpeechEnd { at: Duration, duration: Duration },
+}
+
+impl Event {
+    /// Shifts the event's timestamp later by `by`.
+    pub fn offset(self, by: Duration) -> Self {
+        match self {
+            Event::SpeechStart { at } => Event::SpeechStart { at: at + by },
+            Event::SpeechEnd { at, duration } => Event::SpeechEnd {
+                at: at + by,
+                duration,
+            },
+        }
+    }
+}
+
+/// Receives events from [`VadEvents::push_to`]. Implemented for closures and
+/// channel senders.
+pub trait EventSink {
+    fn send(&mut self, event: Event);
+}
+
+impl<F: FnMut(Event)> EventSink for F {
+    fn send(&mut self, event: Event) {
+        self(event)
+    }
+}
+
+impl EventSink for mpsc::Sender<Event> {
+    fn send(&mut self, event: Event) {
+        // A dropped receiver just means nobody is listening anymore.
+        let _ = mpsc::Sender::send(self, event);
+    }
+}
+
+/// Turns per-frame confidences into [`Event`]s.
+#[derive(Debug, Clone)]
+pub struct VadEvents {
+    detector: HysteresisDetector,
+    min_speech_frames: u64,
+    frames: u64,
+    start: Option<u64>,
+    started: bool,
+}
+
+impl VadEvents {
+    pub fn new(threshold: f32) -> Self {
+        Self::with_detector(HysteresisDetector::new(threshold, threshold))
+    }
+
+    pub fn with_detector(detector: HysteresisDetector) -> Self {
+        VadEvents {
+            detector,
+            min_speech_frames: 0,
+            frames: 0,
+            start: None,
+            started: false,
+        }
+    }
+
+    /// Holds back `SpeechStart` until speech has lasted at least `min`, and
+    /// suppresses shorter runs entirely. The start event is still timed at the
+    /// actual onset.
+    pub fn with_min_speech_duration(mut self, min: Duration) -> Self {
+        self.min_speech_frames = duration_to_frames(min);
+        self
+    }
+
+    /// Feeds the confidence of the next frame, returning the event it
+    /// triggered, if any.
+    pub fn push(&mut self, confidence: Confidence) -> Option<Event> {
+        let frame = self.frames;
+        self.frames += 1;
+        if self.detector.update(confidence) {
+            let start = *self.start.get_or_insert(frame);
+            if self.started || self.frames - start < self.min_speech_frames {
+                return None;
+            }
+            self.started = true;
+            Some(Event::SpeechStart {
+                at: frames_to_duration(start),
+            })
+        } else {
+            let start = self.start.take()?;
+            if !mem::take(&mut self.started) {
+                return None;
+            }
+            Some(Event::SpeechEnd {
+                at: frames_to_duration(frame),
+                duration: frames_to_duration(frame - start),
+            })
+        }
+    }
+
+    /// Ends any speech still in progress at the end of a stream, returning
+    /// the final `SpeechEnd`.
+    pub fn finish(&mut self) -> Option<Event> {
+        self.detector.reset();
+        let start = self.start.take()?;
+        if !mem::take(&mut self.started) {
+            return None;
+        }
+        Some(Event::SpeechEnd {
+            at: frames_to_duration(self.frames),
+            duration: frames_to_duration(self.frames - start),
+        })
+    }
+
+    /// Like [`push`](Self::push), but delivers the event to `sink`.
+    pub fn push_to<S: EventSink>(&mut self, confidence: Confidence, sink: &mut S) {
+        if let Some(event) = self.push(confidence) {
+            sink.send(event);
+        }
+    }
+
+    /// Adapts an iterator of confidences into an iterator of events.
+    pub fn events<I: IntoIterator<Item = Confidence>>(
+        &mut self,
+        confidences: I,
+    ) -> Events<'_, I::IntoIter> {
+        Events {
+            vad: self,
+            confidences: confidences.into_iter(),
+        }
+    }
+
+    pub fn is_speech(&self) -> bool {
+        self.started
+    }
+
+    pub fn reset(&mut self) {
+        self.detector.reset();
+        self.frames = 0;
+        self.start = None;
+        self.started = false;
+    }
+}
+
+/// Iterator returned by [`VadEvents::events`].
+pub struct Events<'a, I> {
+    vad: &'a mut VadEvents,
+    confidences: I,
+}
+
+impl<I: Iterator<Item = Confidence>> Iterator for Events<'_, I> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        for confidence in self.confidences.by_ref() {
+            if let Some(event) = self.vad.push(confidence) {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn confidences(values: &[f32]) -> Vec<Confidence> {
+        values.iter().map(|&c| Confidence::new(c)).collect()
+    }
+
+    #[test]
+    fn check_events() {
+        let mut vad = VadEvents::new(0.5);
+        let events: Vec<Event> = vad.events(confidences(&[0.1, 0.9, 0.8, 0.1])).collect();
+        assert_eq!(
+            events,
+            [
+                Event::SpeechStart {
+                    at: frames_to_duration(1)
+                },
+                Event::SpeechEnd {
+                    at: frames_to_duration(3),
+                    duration: frames_to_duration(2)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn check_finish_ends_speech() {
+        let mut vad = VadEvents::new(0.5);
+        assert_eq!(vad.events(confidences(&[0.1, 0.9, 0.9])).count(), 1);
+        assert_eq!(
+            vad.finish(),
+            Some(Event::SpeechEnd {
+                at: frames_to_duration(3),
+                duration: frames_to_duration(2)
+            })
+        );
+        assert_eq!(vad.finish(), None);
+    }
+
+    #[test]
+    fn check_event_offset() {
+        let event = Event::SpeechEnd {
+            at: frames_to_duration(3),
+            duration: frames_to_duration(2),
+        };
+        assert_eq!(
+            event.offset(frames_to_duration(10)),
+            Event::SpeechEnd {
+                at: frames_to_duration(13),
+                duration: frames_to_duration(2)
+            }
+        );
+    }
+
+    #[test]
+    fn check_min_speech_duration_delays_start() {
+        let mut vad = VadEvents::new(0.5).with_min_speech_duration(frames_to_duration(2));
+        assert_eq!(vad.push(Confidence::new(0.9)), None);
+        assert_eq!(vad.push(Confidence::new(0.1)), None);
+        assert_eq!(vad.push(Confidence::new(0.9)), None);
+        assert_eq!(
+            vad.push(Confidence::new(0.9)),
+            Some(Event::SpeechStart {
+                at: frames_to_duration(2)
+            })
+        );
+    }
+
+    #[test]
+    fn check_event_sinks() {
+        let mut vad = VadEvents::new(0.5);
+        let mut received = Vec::new();
+        let mut callback = |event| received.push(event);
+        for confidence in confidences(&[0.9, 0.1]) {
+            vad.push_to(confidence, &mut callback);
+        }
+        assert_eq!(received.len(), 2);
+
+        let (mut tx, rx) = mpsc::channel();
+        vad.reset();
+        for confidence in confidences(&[0.9, 0.1]) {
+            vad.push_to(confidence, &mut tx);
+        }
+        assert_eq!(rx.try_iter().count(), 2);
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{CobraStream, Confidence, Error, Padding, Result, frames_to_duration};
+
+/// The confidence of one frame from one source of a [`FanIn`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaggedConfidence<T> {
+    pub source: T,
+    /// Start of the frame, relative to the start of the source's audio.
+    pub at: Duration,
+    pub confidence: Confidence,
+}
+
+/// The results sent back by a [`FanIn`], or the error that stopped it.
+pub type TaggedConfidences<T> = Receiver<Result<TaggedConfidence<T>>>;
+
+/// A cheap, cloneable handle for sending audio from one source to a
+/// [`FanIn`].
+#[derive(Debug, Clone)]
+pub struct SourceSender {
+    source: usize,
+    frames: Sender<(usize, Vec<i16>)>,
+}
+
+impl SourceSender {
+    /// Queues a chunk of this source's audio, of any length.
+    pub fn send(&self, pcm: Vec<i16>) -> Result<()> {
+        self.frames
+            .send((self.source, pcm))
+            .map_err(|_| Error::WorkerStopped)
+    }
+}
+
+/// Merges audio from several sources, e.g. two microphones or a microphone
+/// and system loopback, into a single processing thread. Every source keeps
+/// a [`CobraStream`] of its own, so their buffering and post-processing stay
+/// independent, and results are tagged with the source they came from.
+#[derive(Debug)]
+pub struct FanIn<T> {
+    tags: Vec<T>,
+    frames: Sender<(usize, Vec<i16>)>,
+    thread: JoinHandle<Result<Vec<CobraStream>>>,
+}
+
+impl<T: Clone + PartialEq + Send + 'static> FanIn<T> {
+    /// Starts the processing thread for `sources`, each a tag along with the
+    /// stream that processes its audio.
+    pub fn spawn(sources: Vec<(T, CobraStream)>) -> Result<(Self, TaggedConfidences<T>)> {
+        let (tags, streams): (Vec<T>, Vec<CobraStream>) = sources.into_iter().unzip();
+        let (frames, rx) = mpsc::channel();
+        let (results, confidences) = mpsc::channel();
+        let thread_tags = tags.clone();
+        let thread = thread::Builder::new()
+            .name("cobra-fan-in".to_string())
+            .spawn(move || run(thread_tags, streams, rx, results))?;
+        let fan_in = FanIn {
+            tags,
+            frames,
+            thread,
+        };
+        Ok((fan_in, confidences))
+    }
+
+    /// A handle for sending the audio of the source tagged `tag`, or `None`
+    /// if there is no such source.
+    pub fn sender(&self, tag: &T) -> Option<SourceSender> {
+        let source = self.tags.iter().position(|t| t == tag)?;
+        Some(SourceSender {
+            source,
+            frames: self.frames.clone(),
+        })
+    }
+
+    pub fn sources(&self) -> &[T] {
+        &self.tags
+    }
+
+    /// Waits for every source's audio to be processed, including zero-padded
+    /// final frames, and returns the streams. The thread only stops once
+    /// every [`SourceSender`] has been dropped.
+    pub fn join(self) -> Result<Vec<(T, CobraStream)>> {
+        drop(self.frames);
+        let streams = self.thread.join().map_err(|_| Error::WorkerStopped)??;
+        Ok(self.tags.into_iter().zip(streams).collect())
+    }
+}
+
+fn run<T: Clone>(
+    tags: Vec<T>,
+    mut streams: Vec<CobraStream>,
+    frames: Receiver<(usize, Vec<i16>)>,
+    results: Sender<Result<TaggedConfidence<T>>>,
+) -> Result<Vec<CobraStream>> {
+    let mut processed = vec![0; streams.len()];
+    let send = |source: usize, processed: &mut u64, confidences: Result<Vec<Confidence>>| {
+        match confidences {
+            Ok(confidences) => {
+                for confidence in confidences {
+                    let _ = results.send(Ok(TaggedConfidence {
+                        source: tags[source].clone(),
+                        at: frames_to_duration(*processed),
+                        confidence,
+                    }));
+                    *processed += 1;
+                }
+                Ok(())
+            }
+            Err(err) => {
+                let _ = results.send(Err(err.clone()));
+                Err(err)
+            }
+        }
+    };
+    for (source, pcm) in frames {
+        send(source, &mut processed[source], streams[source].push(&pcm))?;
+    }
+    for (source, stream) in streams.iter_mut().enumerate() {
+        send(source, &mut processed[source], stream.finish(Padding::Zero))?;
+    }
+    Ok(streams)
+}
@@ -0,0 +1,118 @@
+use std::ffi::OsStr;
+use std::io::Read;
+use std::process::{Child, ChildStderr, ChildStdout, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{Cobra, Confidence, Error, FrameReader, Padding, Result, frames_to_duration};
+
+/// Decodes any input `ffmpeg` understands by running it as a subprocess and
+/// reading mono 16-bit PCM at Cobra's sample rate from its stdout. Requires
+/// `ffmpeg` to be on the `PATH`. If the reader is dropped without calling
+/// [`finish`](Self::finish), ffmpeg is killed.
+pub struct FfmpegReader {
+    frames: FrameReader<ChildStdout>,
+    child: KillOnDrop,
+    stderr: JoinHandle<String>,
+}
+
+/// How much of ffmpeg's error output is kept, from the end, as that's where
+/// the reason it failed is.
+const STDERR_LIMIT: usize = 16 * 1024;
+
+/// Kills and reaps the child, unless it has already exited.
+struct KillOnDrop(Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        if let Ok(None) = self.0.try_wait() {
+            let _ = self.0.kill();
+        }
+        let _ = self.0.wait();
+    }
+}
+
+impl FfmpegReader {
+    /// Starts decoding `input`, which may be a file path or any URL ffmpeg
+    /// accepts.
+    pub fn open<S: AsRef<OsStr>>(input: S) -> Result<Self> {
+        let mut child = Command::new("ffmpeg")
+            .arg("-nostdin")
+            .args(["-loglevel", "error"])
+            .arg("-i")
+            .arg(input)
+            .args(["-f", "s16le", "-acodec", "pcm_s16le", "-ac", "1", "-ar"])
+            .arg(crate::sample_rate().to_string())
+            .arg("-")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdout = child.stdout.take().ok_or(Error::NullValue)?;
+        let stderr = child.stderr.take().ok_or(Error::NullValue)?;
+        let child = KillOnDrop(child);
+        // Read as it's written, as ffmpeg blocks once the pipe is full.
+        let stderr = thread::Builder::new()
+            .name("ffmpeg-stderr".to_string())
+            .spawn(move || read_tail(stderr))?;
+        Ok(FfmpegReader {
+            frames: FrameReader::new(stdout),
+            child,
+            stderr,
+        })
+    }
+
+    /// Reads the next frame, as per [`FrameReader::read_frame`].
+    pub fn read_frame(&mut self) -> Result<Option<&[i16]>> {
+        self.frames.read_frame()
+    }
+
+    /// Waits for ffmpeg to exit, failing with its error output if decoding
+    /// did not succeed.
+    pub fn finish(self) -> Result<()> {
+        let FfmpegReader {
+            frames,
+            mut child,
+            stderr,
+        } = self;
+        drop(frames);
+        let status = child.0.wait()?;
+        if status.success() {
+            return Ok(());
+        }
+        let stderr = stderr.join().unwrap_or_default();
+        Err(Error::UnsupportedFormat {
+            reason: format!("ffmpeg exited with {}: {}", status, stderr.trim()),
+        })
+    }
+}
+
+/// Reads `pipe` until it closes, keeping the last [`STDERR_LIMIT`] bytes.
+fn read_tail(mut pipe: ChildStderr) -> String {
+    let mut tail = Vec::new();
+    let mut buf = [0; 4096];
+    while let Ok(read @ 1..) = pipe.read(&mut buf) {
+        tail.extend_from_slice(&buf[..read]);
+        if tail.len() > STDERR_LIMIT {
+            tail.drain(..tail.len() - STDERR_LIMIT);
+        }
+    }
+    String::from_utf8_lossy(&tail).into_owned()
+}
+
+/// Runs `input` through `cobra` via ffmpeg, returning the start time and
+/// confidence of every frame. A trailing partial frame is zero-padded.
+pub fn process_file<S: AsRef<OsStr>>(
+    input: S,
+    cobra: &mut Cobra,
+) -> Result<Vec<(Duration, Confidence)>> {
+    let mut reader = FfmpegReader::open(input)?;
+    let mut confidences = Vec::new();
+    while let Some(frame) = reader.read_frame()? {
+        let at = frames_to_duration(confidences.len() as u64);
+        let (confidence, _) = cobra.process_partial(frame, Padding::Zero)?;
+        confidences.push((at, confidence));
+    }
+    reader.finish()?;
+    Ok(confidences)
+}
@@ -0,0 +1,78 @@
+/// A G.711 companding law.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Law {
+    /// µ-law, used in North America and Japan.
+    MuLaw,
+    /// A-law, used in Europe and most other regions.
+    ALaw,
+}
+
+impl Law {
+    pub fn decode_sample(self, byte: u8) -> i16 {
+        match self {
+            Law::MuLaw => decode_mulaw(byte),
+            Law::ALaw => decode_alaw(byte),
+        }
+    }
+
+    /// Appends the linear samples encoded by `bytes` to `out`.
+    pub fn decode_into(self, bytes: &[u8], out: &mut Vec<i16>) {
+        out.extend(bytes.iter().map(|&b| self.decode_sample(b)));
+    }
+
+    pub fn decode(self, bytes: &[u8]) -> Vec<i16> {
+        let mut out = Vec::with_capacity(bytes.len());
+        self.decode_into(bytes, &mut out);
+        out
+    }
+}
+
+pub fn decode_mulaw(byte: u8) -> i16 {
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = (byte & 0x0f) as i16;
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    if byte & 0x80 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+pub fn decode_alaw(byte: u8) -> i16 {
+    let byte = byte ^ 0x55;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = (byte & 0x0f) as i16;
+    let magnitude = match exponent {
+        0 => (mantissa << 4) + 8,
+        _ => ((mantissa << 4) + 0x108) << (exponent - 1),
+    };
+    if byte & 0x80 != 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_mulaw() {
+        assert_eq!(decode_mulaw(0xff), 0);
+        assert_eq!(decode_mulaw(0x7f), 0);
+        assert_eq!(decode_mulaw(0x80), 32124);
+        assert_eq!(decode_mulaw(0x00), -32124);
+        assert_eq!(decode_mulaw(0xfe), 8);
+    }
+
+    #[test]
+    fn check_alaw() {
+        assert_eq!(decode_alaw(0xd5), 8);
+        assert_eq!(decode_alaw(0x55), -8);
+        assert_eq!(decode_alaw(0xaa), 32256);
+        assert_eq!(decode_alaw(0x2a), -32256);
+        assert_eq!(Law::ALaw.decode(&[0xd5, 0x55]), [8, -8]);
+    }
+}
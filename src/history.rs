@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use crate::{Confidence, duration_to_frames};
+
+/// Retains the most recent confidences, oldest first, as a contiguous slice.
+#[derive(Debug, Clone)]
+pub struct ConfidenceHistory {
+    // Holds up to twice the capacity so that old entries only need to be
+    // shifted out once every `capacity` pushes.
+    buf: Vec<Confidence>,
+    capacity: usize,
+}
+
+impl ConfidenceHistory {
+    /// Keeps the confidences of the last `frames` frames.
+    pub fn new(frames: usize) -> Self {
+        let capacity = frames.max(1);
+        ConfidenceHistory {
+            buf: Vec::with_capacity(2 * capacity),
+            capacity,
+        }
+    }
+
+    /// Keeps the confidences covering the last `duration` of audio.
+    pub fn with_duration(duration: Duration) -> Self {
+        Self::new(duration_to_frames(duration) as usize)
+    }
+
+    pub fn push(&mut self, confidence: Confidence) {
+        if self.buf.len() == 2 * self.capacity {
+            self.buf.drain(..self.capacity);
+        }
+        self.buf.push(confidence);
+    }
+
+    pub fn as_slice(&self) -> &[Confidence] {
+        &self.buf[self.buf.len().saturating_sub(self.capacity)..]
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_history_keeps_latest() {
+        let mut history = ConfidenceHistory::new(3);
+        assert!(history.as_slice().is_empty());
+        for i in 0..8u8 {
+            history.push(Confidence::new(i as f32 / 10.0));
+            let expected: Vec<Confidence> = (i.saturating_sub(2)..=i)
+                .map(|j| Confidence::new(j as f32 / 10.0))
+                .collect();
+            assert_eq!(history.as_slice(), expected);
+        }
+    }
+}
@@ -1,119 +1,452 @@
-mod ffi;
+mod access_key;
+pub mod adaptive;
+pub mod aiff;
+#[cfg(feature = "async")]
+pub mod async_vad;
+#[cfg(feature = "batch")]
+pub mod batch;
+mod builder;
+pub mod calibration;
+mod cancel;
+pub mod collector;
+mod compat;
+mod confidence;
+#[cfg(feature = "decode")]
+pub mod decode;
+pub mod detector;
+#[cfg(feature = "dlopen")]
+mod dlopen;
+pub mod downmix;
+pub mod endpoint;
+mod error;
+pub mod events;
+pub mod fanin;
+#[cfg(feature = "ffmpeg")]
+pub mod ffmpeg;
+pub mod g711;
+mod history;
+pub mod limiter;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod multichannel;
+#[cfg(feature = "opus")]
+pub mod opus;
+pub mod pool;
+pub mod preprocess;
+mod preroll;
+pub mod queue;
+mod reader;
+pub mod resample;
+mod retry;
+mod sample;
+pub mod segmenter;
+pub mod session;
+mod shared;
+pub mod smoothing;
+pub mod state;
+pub mod stats;
+mod stream;
+/// The raw bindings, for calling functions the safe API doesn't cover yet.
+/// With the `dlopen` or `wasm` features the library isn't linked, so these
+/// can't be called.
+#[cfg(feature = "unsafe-ffi")]
+pub mod sys;
+pub mod telephony;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod wasm;
+#[cfg(feature = "wav")]
+pub mod wav;
+pub mod window;
+pub mod worker;
+
+#[cfg(all(feature = "dlopen", feature = "wasm"))]
+compile_error!("The dlopen and wasm features can't be enabled together");
 
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::fmt;
-use std::os::raw::c_uint;
+use std::mem;
+#[cfg(feature = "dlopen")]
+use std::path::Path;
 use std::ptr;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+#[cfg(feature = "dlopen")]
+use dlopen as ffi;
+#[cfg(not(any(feature = "dlopen", all(feature = "wasm", target_arch = "wasm32"))))]
+use pv_cobra_redux_sys as ffi;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+use wasm as ffi;
+
+// `std::time::Instant` panics on wasm32-unknown-unknown, so the browser's
+// clock is used there instead.
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) use std::time::Instant;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub(crate) use web_time::Instant;
 
+use crate::stats::{LatencyRecorder, LatencyStats};
+
+pub use access_key::AccessKey;
+pub use builder::CobraBuilder;
+pub use cancel::CancellationToken;
+pub use compat::{Incompatibility, verify_compatibility};
+pub use confidence::Confidence;
+#[cfg(feature = "dlopen")]
+pub use dlopen::load_library;
+pub use error::{Error, Result};
+pub use history::ConfidenceHistory;
+pub use pv_cobra_redux_sys::pv_cobra;
+pub use reader::FrameReader;
+pub use retry::RetryPolicy;
+pub use sample::{Endianness, IntoPcm16, SampleWidth};
+pub use shared::SharedCobra;
+pub use stream::CobraStream;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub use wasm::load_module;
+
+/// Sample rate the engine expects, in Hz. With the `dlopen` or `wasm` features,
+/// panics if the library isn't loaded and can't be.
 pub fn sample_rate() -> i32 {
     unsafe { ffi::pv_sample_rate() }
 }
 
+/// Number of samples the engine processes at a time. With the `dlopen` or
+/// `wasm` features, panics if the library isn't loaded and can't be.
 pub fn frame_length() -> i32 {
     unsafe { ffi::pv_cobra_frame_length() }
 }
 
+/// Duration of audio covered by a single frame.
+pub fn frame_duration() -> Duration {
+    frames_to_duration(1)
+}
+
+pub(crate) fn frames_to_duration(frames: u64) -> Duration {
+    let nanos = frames as u128 * frame_length() as u128 * 1_000_000_000 / sample_rate() as u128;
+    Duration::from_nanos(nanos as u64)
+}
+
+/// Number of frames needed to cover `duration`, rounding up.
+pub(crate) fn duration_to_frames(duration: Duration) -> u64 {
+    let frame = frame_duration().as_nanos();
+    duration.as_nanos().div_ceil(frame) as u64
+}
+
+pub(crate) fn samples_to_duration(samples: u64) -> Duration {
+    let nanos = samples as u128 * 1_000_000_000 / sample_rate() as u128;
+    Duration::from_nanos(nanos as u64)
+}
+
+pub(crate) fn duration_to_samples(duration: Duration) -> u64 {
+    (duration.as_nanos() * sample_rate() as u128 / 1_000_000_000) as u64
+}
+
+/// Locks `mutex`, ignoring poisoning. Nothing this crate guards with a mutex
+/// is left inconsistent by a panic: a `Cobra` stays usable after one mid-call,
+/// and shared state is only ever changed between statements.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Version of the engine library. With the `dlopen` or `wasm` features, panics
+/// if the library isn't loaded and can't be.
 pub fn lib_version() -> &'static str {
     let cstr = unsafe { CStr::from_ptr(ffi::pv_cobra_version()) };
     cstr.to_str().unwrap()
 }
 
-#[derive(Debug, Clone)]
-pub enum Error {
-    NullValue,
-    OutOfMemory,
-    IoError,
-    InvalidArgument,
-    StopIteration,
-    KeyError,
-    InvalidState,
-    RuntimeError,
-    ActivationError,
-    ActivationLimitReached,
-    ActivationThrottled,
-    ActivationRefused,
-    UnknownError(c_uint),
-}
-
-impl From<ffi::pv_status_t> for Error {
-    fn from(status: ffi::pv_status_t) -> Self {
-        match status {
-            ffi::pv_status_t_PV_STATUS_OUT_OF_MEMORY => Error::OutOfMemory,
-            ffi::pv_status_t_PV_STATUS_IO_ERROR => Error::IoError,
-            ffi::pv_status_t_PV_STATUS_INVALID_ARGUMENT => Error::InvalidArgument,
-            ffi::pv_status_t_PV_STATUS_STOP_ITERATION => Error::StopIteration,
-            ffi::pv_status_t_PV_STATUS_KEY_ERROR => Error::KeyError,
-            ffi::pv_status_t_PV_STATUS_INVALID_STATE => Error::InvalidState,
-            ffi::pv_status_t_PV_STATUS_RUNTIME_ERROR => Error::RuntimeError,
-            ffi::pv_status_t_PV_STATUS_ACTIVATION_ERROR => Error::ActivationError,
-            ffi::pv_status_t_PV_STATUS_ACTIVATION_LIMIT_REACHED => Error::ActivationLimitReached,
-            ffi::pv_status_t_PV_STATUS_ACTIVATION_THROTTLED => Error::ActivationThrottled,
-            ffi::pv_status_t_PV_STATUS_ACTIVATION_REFUSED => Error::ActivationRefused,
-            _ => Error::UnknownError(status as c_uint),
-        }
-    }
+/// Which engine binary is in use, e.g. for production builds to report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibraryInfo {
+    /// Version reported by the library.
+    pub version: &'static str,
+    /// The library linked at build time, as a path under the `lib` directory
+    /// of Cobra's repository, or its file name if it was given with
+    /// `PV_COBRA_LIB_PATH`. `None` if it was found through pkg-config or is
+    /// loaded at runtime.
+    pub linked: Option<&'static str>,
+    /// SHA-256 of the linked library, as a hex string.
+    pub sha256: Option<&'static str>,
 }
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Error::NullValue => write!(f, "unexpected null value"),
-            Error::OutOfMemory => write!(f, "out of memory"),
-            Error::IoError => write!(f, "I/O error"),
-            Error::InvalidArgument => write!(f, "invalid argument"),
-            Error::StopIteration => write!(f, "stop iteration"),
-            Error::KeyError => write!(f, "key error"),
-            Error::InvalidState => write!(f, "invalid state"),
-            Error::RuntimeError => write!(f, "runtime error"),
-            Error::ActivationError => write!(f, "activation error"),
-            Error::ActivationLimitReached => write!(f, "activation limit reached"),
-            Error::ActivationThrottled => write!(f, "activation throttled"),
-            Error::ActivationRefused => write!(f, "activation refused"),
-            Error::UnknownError(c) => write!(f, "non-zero status returned: {}", c),
-        }
+pub fn library_info() -> LibraryInfo {
+    LibraryInfo {
+        version: lib_version(),
+        linked: pv_cobra_redux_sys::LINKED_LIB,
+        sha256: pv_cobra_redux_sys::LINKED_LIB_SHA256,
     }
 }
 
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        None
+/// How [`Cobra::process_partial`] fills the missing end of a short frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Padding {
+    /// Fill with silence.
+    #[default]
+    Zero,
+    /// Reflect the available samples back and forth.
+    Mirror,
+}
+
+fn pad_frame(pcm: &[i16], frame_length: usize, padding: Padding) -> Vec<i16> {
+    let mut frame = Vec::with_capacity(frame_length);
+    frame.extend_from_slice(pcm);
+    match padding {
+        Padding::Zero => frame.resize(frame_length, 0),
+        Padding::Mirror => {
+            let period = 2 * pcm.len();
+            for i in 0..frame_length - pcm.len() {
+                let j = i % period;
+                let sample = if j < pcm.len() {
+                    pcm[pcm.len() - 1 - j]
+                } else {
+                    pcm[j - pcm.len()]
+                };
+                frame.push(sample);
+            }
+        }
     }
+    frame
 }
 
 pub struct Cobra {
     cobra: *mut ffi::pv_cobra,
+    latency: LatencyRecorder,
 }
 
 impl Cobra {
-    pub fn new<S: Into<Vec<u8>>>(access_key: S) -> Result<Self, Error> {
-        let access_key = CString::new(access_key).map_err(|_err| Error::NullValue)?;
+    pub fn new<K: Into<AccessKey>>(access_key: K) -> Result<Self> {
+        let access_key =
+            CString::new(access_key.into().expose()).map_err(|_err| Error::NullValue)?;
+        Self::init(&access_key)
+    }
+
+    pub fn new_with_retry<K: Into<AccessKey>>(access_key: K, policy: &RetryPolicy) -> Result<Self> {
+        let access_key =
+            CString::new(access_key.into().expose()).map_err(|_err| Error::NullValue)?;
+        policy.run(|| Self::init(&access_key))
+    }
+
+    /// Loads the Cobra library at `path`, see [`load_library`], and creates an
+    /// instance with it.
+    #[cfg(feature = "dlopen")]
+    pub fn with_library<P: AsRef<Path>, K: Into<AccessKey>>(
+        path: P,
+        access_key: K,
+    ) -> Result<Self> {
+        load_library(path)?;
+        Self::new(access_key)
+    }
+
+    pub fn builder<K: Into<AccessKey>>(access_key: K) -> CobraBuilder {
+        CobraBuilder::new(access_key)
+    }
+
+    pub(crate) fn init(access_key: &CStr) -> Result<Self> {
+        if !cfg!(feature = "dlopen") && !pv_cobra_redux_sys::LIB_AVAILABLE {
+            return Err(Error::UnsupportedPlatform);
+        }
+        #[cfg(feature = "dlopen")]
+        dlopen::ensure_loaded()?;
+        #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+        wasm::ensure_loaded()?;
+        verify_compatibility()?;
         let mut cobra: *mut ffi::pv_cobra = ptr::null_mut();
         let status = unsafe { ffi::pv_cobra_init(access_key.as_ptr(), &mut cobra) };
         if status != 0 {
-            Err(Error::from(status))
+            Err(Error::from_status(status))
         } else if cobra.is_null() {
             Err(Error::NullValue)
         } else {
-            Ok(Cobra { cobra })
+            Ok(Cobra {
+                cobra,
+                latency: LatencyRecorder::default(),
+            })
         }
     }
 
-    pub fn process(&mut self, pcm: &[i16]) -> Result<f32, Error> {
+    pub fn sample_rate(&self) -> i32 {
+        sample_rate()
+    }
+
+    pub fn frame_length(&self) -> i32 {
+        frame_length()
+    }
+
+    pub fn version(&self) -> &'static str {
+        lib_version()
+    }
+
+    pub fn process(&mut self, pcm: &[i16]) -> Result<Confidence> {
+        #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+        wasm::ensure_loaded()?;
+        let expected = self.frame_length() as usize;
+        if pcm.len() != expected {
+            return Err(Error::InvalidFrameLength {
+                expected,
+                got: pcm.len(),
+            });
+        }
         let mut confidence: f32 = 0.0;
+        let start = Instant::now();
         let status = unsafe { ffi::pv_cobra_process(self.cobra, pcm.as_ptr(), &mut confidence) };
+        self.latency.record(start.elapsed());
         if status != 0 {
-            Err(Error::from(status))
+            Err(Error::from_status(status))
+        } else {
+            Ok(Confidence::new(confidence))
+        }
+    }
+
+    /// How long [`process`](Self::process) has spent in the engine per
+    /// frame, e.g. to spot a performance regression after upgrading it.
+    pub fn latency_stats(&self) -> LatencyStats {
+        self.latency.stats()
+    }
+
+    /// Processes a frame of little-endian 16-bit PCM, e.g. as received from a
+    /// socket. `pcm` must hold exactly `frame_length() * 2` bytes.
+    pub fn process_bytes(&mut self, pcm: &[u8]) -> Result<Confidence> {
+        let expected = self.frame_length() as usize * 2;
+        if pcm.len() != expected {
+            return Err(Error::InvalidByteLength {
+                expected,
+                got: pcm.len(),
+            });
+        }
+        if cfg!(target_endian = "little") {
+            // Safe: every bit pattern is a valid i16.
+            let (prefix, samples, suffix) = unsafe { pcm.align_to::<i16>() };
+            if prefix.is_empty() && suffix.is_empty() {
+                return self.process(samples);
+            }
+        }
+        self.process_bytes_with(pcm, SampleWidth::I16, Endianness::Little)
+    }
+
+    /// Processes a frame of PCM with samples of the given width and byte
+    /// order, which are scaled to 16 bits. `pcm` must hold exactly
+    /// `frame_length() * width.bytes()` bytes.
+    pub fn process_bytes_with(
+        &mut self,
+        pcm: &[u8],
+        width: SampleWidth,
+        endianness: Endianness,
+    ) -> Result<Confidence> {
+        let expected = self.frame_length() as usize * width.bytes();
+        if pcm.len() != expected {
+            return Err(Error::InvalidByteLength {
+                expected,
+                got: pcm.len(),
+            });
+        }
+        let mut samples = Vec::with_capacity(self.frame_length() as usize);
+        sample::decode_bytes(pcm, width, endianness, &mut samples);
+        self.process(&samples)
+    }
+
+    /// Processes a frame of samples in any supported format, scaling them to
+    /// 16 bits first. `pcm` must hold exactly `frame_length()` samples.
+    pub fn process_samples<S: IntoPcm16>(&mut self, pcm: &[S]) -> Result<Confidence> {
+        let samples: Vec<i16> = pcm.iter().map(|s| s.into_pcm16()).collect();
+        self.process(&samples)
+    }
+
+    /// Processes the final, possibly short, chunk of a stream by padding it up
+    /// to a full frame. Returns the confidence along with whether any padding
+    /// was applied. `pcm` must hold between 1 and `frame_length()` samples.
+    pub fn process_partial(&mut self, pcm: &[i16], padding: Padding) -> Result<(Confidence, bool)> {
+        let expected = self.frame_length() as usize;
+        if pcm.is_empty() || pcm.len() > expected {
+            return Err(Error::InvalidFrameLength {
+                expected,
+                got: pcm.len(),
+            });
+        }
+        if pcm.len() == expected {
+            return Ok((self.process(pcm)?, false));
+        }
+        let frame = pad_frame(pcm, expected, padding);
+        Ok((self.process(&frame)?, true))
+    }
+
+    /// Moves this instance onto a dedicated thread, returning a handle for
+    /// sending it audio and a receiver for the resulting confidences.
+    pub fn spawn_worker(self) -> Result<(worker::Worker, worker::Confidences)> {
+        worker::Worker::spawn(CobraStream::new(self))
+    }
+
+    /// Returns the underlying engine handle. The handle remains owned by this
+    /// `Cobra` and must not be deleted or used after it is dropped.
+    pub fn as_raw(&self) -> *mut pv_cobra {
+        self.cobra
+    }
+
+    /// Releases ownership of the underlying engine handle without deleting
+    /// it. The caller becomes responsible for calling `pv_cobra_delete`, or
+    /// for handing it back via [`Cobra::from_raw`].
+    pub fn into_raw(mut self) -> *mut pv_cobra {
+        mem::replace(&mut self.cobra, ptr::null_mut())
+    }
+
+    /// Takes ownership of an engine handle created by `pv_cobra_init`.
+    ///
+    /// # Safety
+    ///
+    /// `cobra` must be a live handle returned by `pv_cobra_init` (or by
+    /// [`Cobra::into_raw`]) that is not owned or used anywhere else. The
+    /// returned `Cobra` deletes it when dropped.
+    pub unsafe fn from_raw(cobra: *mut pv_cobra) -> Result<Self> {
+        if cobra.is_null() {
+            Err(Error::NullValue)
         } else {
-            Ok(confidence)
+            Ok(Cobra {
+                cobra,
+                latency: LatencyRecorder::default(),
+            })
         }
     }
+
+    /// Releases the underlying engine. Dropping a `Cobra` does the same, but
+    /// this makes the point of teardown explicit.
+    pub fn close(mut self) -> Result<()> {
+        let cobra = mem::replace(&mut self.cobra, ptr::null_mut());
+        if cobra.is_null() {
+            return Err(Error::NullValue);
+        }
+        unsafe {
+            ffi::pv_cobra_delete(cobra);
+        }
+        Ok(())
+    }
+}
+
+// The engine handle isn't tied to the thread that created it, so a `Cobra`
+// can be moved to a worker thread. It is not `Sync`: all calls go through
+// `&mut self`, so a handle is never used from two threads at once. With the
+// `wasm` feature the module is only loaded on one thread, so elsewhere
+// processing fails with `Error::LibraryLoad`.
+unsafe impl Send for Cobra {}
+
+impl fmt::Debug for Cobra {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cobra")
+            .field("version", &self.version())
+            .field("sample_rate", &self.sample_rate())
+            .field("frame_length", &self.frame_length())
+            .finish()
+    }
 }
 
 impl Drop for Cobra {
     fn drop(&mut self) {
-        unsafe {
-            ffi::pv_cobra_delete(self.cobra);
+        if !self.cobra.is_null() {
+            unsafe {
+                ffi::pv_cobra_delete(self.cobra);
+            }
         }
     }
 }
@@ -139,4 +472,23 @@ mod test {
         // Just make sure it's callable
         lib_version();
     }
+
+    #[test]
+    fn check_library_info() {
+        let info = library_info();
+        assert_eq!(info.version, lib_version());
+        if let Some(sha256) = info.sha256 {
+            assert_eq!(sha256.len(), 64);
+            assert!(sha256.bytes().all(|b| b.is_ascii_hexdigit()));
+        }
+    }
+
+    #[test]
+    fn check_pad_frame() {
+        assert_eq!(pad_frame(&[1, 2, 3], 6, Padding::Zero), [1, 2, 3, 0, 0, 0]);
+        assert_eq!(
+            pad_frame(&[1, 2, 3], 10, Padding::Mirror),
+            [1, 2, 3, 3, 2, 1, 1, 2, 3, 3]
+        );
+    }
 }
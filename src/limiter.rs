@@ -0,0 +1,187 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use crate::{AccessKey, Cobra, CobraBuilder, Error, Instant, Result, lock};
+
+/// Counts the instances alive at once.
+#[derive(Debug)]
+struct Slots {
+    live: Mutex<usize>,
+    freed: Condvar,
+    max: usize,
+}
+
+impl Slots {
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        lock(&self.live)
+    }
+
+    /// Takes a slot, waiting until `deadline` (or forever if `None`) for one
+    /// to be freed. Returns whether a slot was taken.
+    fn acquire(&self, deadline: Option<Instant>) -> bool {
+        let mut live = self.lock();
+        while *live >= self.max {
+            let Some(deadline) = deadline else {
+                live = self
+                    .freed
+                    .wait(live)
+                    .unwrap_or_else(PoisonError::into_inner);
+                continue;
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            live = self
+                .freed
+                .wait_timeout(live, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        *live += 1;
+        true
+    }
+
+    fn release(&self) {
+        *self.lock() -= 1;
+        self.freed.notify_one();
+    }
+}
+
+/// Caps the number of Cobra instances alive at once, e.g. to stay within the
+/// concurrency limit of an access key. Requests beyond the cap either wait
+/// for an instance to be dropped or fail with [`Error::AtCapacity`].
+///
+/// If activation itself reports that the key's limit was reached, that is
+/// surfaced as [`Error::AtCapacity`] too.
+#[derive(Debug, Clone)]
+pub struct InstanceLimiter {
+    builder: CobraBuilder,
+    slots: Arc<Slots>,
+}
+
+impl InstanceLimiter {
+    pub fn new<K: Into<AccessKey>>(access_key: K, max: usize) -> Self {
+        Self::with_builder(CobraBuilder::new(access_key), max)
+    }
+
+    /// Creates instances with `builder`, e.g. to retry transient failures.
+    pub fn with_builder(builder: CobraBuilder, max: usize) -> Self {
+        assert!(max > 0, "at least one instance must be allowed");
+        InstanceLimiter {
+            builder,
+            slots: Arc::new(Slots {
+                live: Mutex::new(0),
+                freed: Condvar::new(),
+                max,
+            }),
+        }
+    }
+
+    pub fn max(&self) -> usize {
+        self.slots.max
+    }
+
+    /// Number of instances currently alive.
+    pub fn live(&self) -> usize {
+        *self.slots.lock()
+    }
+
+    /// Creates an instance, waiting for another to be dropped if the cap has
+    /// been reached.
+    pub fn acquire(&self) -> Result<LimitedCobra> {
+        self.slots.acquire(None);
+        self.build()
+    }
+
+    /// Like [`acquire`](Self::acquire), but gives up with
+    /// [`Error::AtCapacity`] after waiting for `timeout`.
+    pub fn acquire_timeout(&self, timeout: Duration) -> Result<LimitedCobra> {
+        if !self.slots.acquire(Some(Instant::now() + timeout)) {
+            return Err(Error::AtCapacity {
+                limit: self.slots.max,
+            });
+        }
+        self.build()
+    }
+
+    /// Creates an instance if the cap hasn't been reached, and fails with
+    /// [`Error::AtCapacity`] otherwise.
+    pub fn try_acquire(&self) -> Result<LimitedCobra> {
+        self.acquire_timeout(Duration::ZERO)
+    }
+
+    /// Builds an instance for a slot that has already been taken.
+    fn build(&self) -> Result<LimitedCobra> {
+        match self.builder.clone().build() {
+            Ok(cobra) => Ok(LimitedCobra {
+                cobra,
+                slots: Arc::clone(&self.slots),
+            }),
+            Err(err) => {
+                self.slots.release();
+                match err {
+                    Error::ActivationLimitReached { .. } => Err(Error::AtCapacity {
+                        limit: self.slots.max,
+                    }),
+                    err => Err(err),
+                }
+            }
+        }
+    }
+}
+
+/// An instance created through an [`InstanceLimiter`]. Dropping it frees up
+/// its slot.
+#[derive(Debug)]
+pub struct LimitedCobra {
+    cobra: Cobra,
+    slots: Arc<Slots>,
+}
+
+impl Deref for LimitedCobra {
+    type Target = Cobra;
+
+    fn deref(&self) -> &Cobra {
+        &self.cobra
+    }
+}
+
+impl DerefMut for LimitedCobra {
+    fn deref_mut(&mut self) -> &mut Cobra {
+        &mut self.cobra
+    }
+}
+
+impl Drop for LimitedCobra {
+    fn drop(&mut self) {
+        self.slots.release();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn check_slots() {
+        let slots = Arc::new(Slots {
+            live: Mutex::new(0),
+            freed: Condvar::new(),
+            max: 2,
+        });
+        assert!(slots.acquire(Some(Instant::now())));
+        assert!(slots.acquire(Some(Instant::now())));
+        assert!(!slots.acquire(Some(Instant::now() + Duration::from_millis(10))));
+        let waiter = {
+            let slots = Arc::clone(&slots);
+            thread::spawn(move || slots.acquire(None))
+        };
+        slots.release();
+        assert!(waiter.join().unwrap());
+        assert_eq!(*slots.lock(), 2);
+    }
+}
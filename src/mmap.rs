@@ -0,0 +1,261 @@
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+use memmap2::Mmap;
+
+use crate::downmix::{Downmix, downmix_into};
+use crate::sample::decode_bytes;
+use crate::{
+    Cobra, Confidence, Endianness, Error, Padding, Result, SampleWidth, samples_to_duration,
+};
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+/// A WAV file mapped into memory, so that multi-hour recordings can be
+/// analyzed in constant memory and from any offset. Supports 16 and 24-bit
+/// integer PCM.
+///
+/// The file must not be modified while it is mapped.
+pub struct MappedWav {
+    mmap: Mmap,
+    data: Range<usize>,
+    channels: usize,
+    width: SampleWidth,
+    sample_rate: u32,
+    /// Index of the next sample frame to read.
+    position: u64,
+    interleaved: Vec<i16>,
+    frame: Vec<i16>,
+}
+
+impl MappedWav {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        // Safe as long as nobody truncates or rewrites the file while it is
+        // mapped, which the type's documentation rules out.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let format = parse(&mmap)?;
+        Ok(MappedWav {
+            mmap,
+            data: format.data,
+            channels: format.channels,
+            width: format.width,
+            sample_rate: format.sample_rate,
+            position: 0,
+            interleaved: Vec::new(),
+            frame: Vec::new(),
+        })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Number of sample frames, i.e. samples per channel, in the file.
+    pub fn len(&self) -> u64 {
+        (self.data.len() / self.block_align()) as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.sample_duration(self.len())
+    }
+
+    /// Index of the next sample frame to be read.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Moves to the sample frame at `position`, clamped to the end of the
+    /// file.
+    pub fn seek(&mut self, position: u64) {
+        self.position = position.min(self.len());
+    }
+
+    /// Reads the next `frame_length` sample frames averaged down to mono, or
+    /// fewer at the end of the file. Returns `None` once the end is reached.
+    /// A trailing partial sample frame, as in a truncated file, is ignored.
+    pub fn read_frame(&mut self, frame_length: usize) -> Option<&[i16]> {
+        let block_align = self.block_align();
+        let start = self.data.start + self.position as usize * block_align;
+        let blocks = (self.data.end.saturating_sub(start) / block_align).min(frame_length);
+        if blocks == 0 {
+            return None;
+        }
+        let end = start + blocks * block_align;
+        self.position += blocks as u64;
+        self.interleaved.clear();
+        decode_bytes(
+            &self.mmap[start..end],
+            self.width,
+            Endianness::Little,
+            &mut self.interleaved,
+        );
+        self.frame.clear();
+        downmix_into(
+            &self.interleaved,
+            self.channels,
+            Downmix::Average,
+            &mut self.frame,
+        );
+        Some(&self.frame)
+    }
+
+    /// Runs the rest of the file through `cobra`, returning the start time
+    /// of every frame, relative to the start of the file, along with its
+    /// confidence. The file must be at Cobra's sample rate.
+    pub fn process(&mut self, cobra: &mut Cobra) -> Result<Vec<(Duration, Confidence)>> {
+        if self.sample_rate != cobra.sample_rate() as u32 {
+            return Err(Error::UnsupportedFormat {
+                reason: format!(
+                    "sample rate is {} Hz, expected {} Hz",
+                    self.sample_rate,
+                    cobra.sample_rate()
+                ),
+            });
+        }
+        let frame_length = cobra.frame_length() as usize;
+        let mut confidences = Vec::new();
+        loop {
+            let at = self.sample_duration(self.position);
+            let Some(frame) = self.read_frame(frame_length) else {
+                return Ok(confidences);
+            };
+            let (confidence, _) = cobra.process_partial(frame, Padding::Zero)?;
+            confidences.push((at, confidence));
+        }
+    }
+
+    fn block_align(&self) -> usize {
+        self.channels * self.width.bytes()
+    }
+
+    fn sample_duration(&self, samples: u64) -> Duration {
+        if self.sample_rate as i32 == crate::sample_rate() {
+            return samples_to_duration(samples);
+        }
+        Duration::from_nanos((samples as u128 * 1_000_000_000 / self.sample_rate as u128) as u64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Format {
+    data: Range<usize>,
+    channels: usize,
+    width: SampleWidth,
+    sample_rate: u32,
+}
+
+fn parse(bytes: &[u8]) -> Result<Format> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(unsupported("not a WAV file"));
+    }
+    let mut format = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let start = offset + 8;
+        let end = start.saturating_add(size).min(bytes.len());
+        match id {
+            b"fmt " => format = Some(parse_fmt(&bytes[start..end])?),
+            b"data" => {
+                let (channels, width, sample_rate) =
+                    format.ok_or_else(|| unsupported("data chunk before fmt chunk"))?;
+                return Ok(Format {
+                    data: start..end,
+                    channels,
+                    width,
+                    sample_rate,
+                });
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length.
+        offset = end + (size & 1);
+    }
+    Err(unsupported("missing data chunk"))
+}
+
+fn parse_fmt(chunk: &[u8]) -> Result<(usize, SampleWidth, u32)> {
+    if chunk.len() < 16 {
+        return Err(unsupported("truncated fmt chunk"));
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([chunk[i], chunk[i + 1]]);
+    let mut tag = u16_at(0);
+    if tag == WAVE_FORMAT_EXTENSIBLE && chunk.len() >= 26 {
+        // The first two bytes of the sub-format GUID hold the actual tag.
+        tag = u16_at(24);
+    }
+    let channels = u16_at(2) as usize;
+    let sample_rate = u32::from_le_bytes(chunk[4..8].try_into().unwrap());
+    let width = match (tag, u16_at(14)) {
+        (WAVE_FORMAT_PCM, 16) => SampleWidth::I16,
+        (WAVE_FORMAT_PCM, 24) => SampleWidth::I24,
+        (tag, bits) => {
+            return Err(unsupported(format!(
+                "{}-bit samples with format tag {:#x}",
+                bits, tag
+            )));
+        }
+    };
+    if channels == 0 {
+        return Err(unsupported("no channels"));
+    }
+    Ok((channels, width, sample_rate))
+}
+
+fn unsupported<S: Into<String>>(reason: S) -> Error {
+    Error::UnsupportedFormat {
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_parse() {
+        let mut wav = b"RIFF\0\0\0\0WAVE".to_vec();
+        wav.extend(b"fmt \x10\0\0\0\x01\0\x02\0\x80\x3e\0\0\0\xfa\0\0\x04\0\x10\0");
+        wav.extend(b"LIST\x03\0\0\0abc\0");
+        wav.extend(b"data\x08\0\0\0\x01\0\x02\0\x03\0\x04\0");
+        assert_eq!(
+            parse(&wav).unwrap(),
+            Format {
+                data: 56..64,
+                channels: 2,
+                width: SampleWidth::I16,
+                sample_rate: 16000,
+            }
+        );
+        assert!(parse(b"RIFF\0\0\0\0WAVE").is_err());
+    }
+
+    #[test]
+    fn check_read_frame_ignores_partial_block() {
+        let mut wav = b"RIFF\0\0\0\0WAVE".to_vec();
+        wav.extend(b"fmt \x10\0\0\0\x01\0\x02\0\x80\x3e\0\0\0\xfa\0\0\x04\0\x10\0");
+        // Two sample frames, then one truncated mid-sample.
+        wav.extend(b"data\x0b\0\0\0\x02\0\x04\0\x06\0\x08\0\x01\0\x02");
+        let path = std::env::temp_dir().join(format!("pv_cobra_redux_wav_{}", std::process::id()));
+        std::fs::write(&path, &wav).unwrap();
+        let mut mapped = MappedWav::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mapped.len(), 2);
+        assert_eq!(mapped.read_frame(512), Some(&[3, 7][..]));
+        assert_eq!(mapped.read_frame(512), None);
+        assert_eq!(mapped.position(), 2);
+    }
+}
@@ -0,0 +1,121 @@
+use std::mem;
+
+use crate::{AccessKey, Cobra, Confidence, Result};
+
+/// The confidences of every channel for one frame of multi-channel audio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelConfidences {
+    pub confidences: Vec<Confidence>,
+    /// Index of the channel with the highest confidence.
+    pub best_channel: usize,
+}
+
+impl ChannelConfidences {
+    pub fn best(&self) -> Confidence {
+        self.confidences[self.best_channel]
+    }
+}
+
+/// Runs a separate Cobra instance on each channel of interleaved audio, e.g.
+/// to tell which microphone of an array is picking up the speaker.
+pub struct MultiChannelVad {
+    cobras: Vec<Cobra>,
+    frame_length: usize,
+    buf: Vec<i16>,
+    channel: Vec<i16>,
+}
+
+impl MultiChannelVad {
+    /// Creates one Cobra instance per channel.
+    pub fn new<K: Into<AccessKey>>(access_key: K, channels: usize) -> Result<Self> {
+        let access_key = access_key.into();
+        let cobras = (0..channels)
+            .map(|_| Cobra::new(access_key.clone()))
+            .collect::<Result<_>>()?;
+        Ok(Self::from_instances(cobras))
+    }
+
+    /// Uses `cobras[i]` for channel `i`.
+    pub fn from_instances(cobras: Vec<Cobra>) -> Self {
+        assert!(!cobras.is_empty(), "at least one channel is required");
+        let frame_length = cobras[0].frame_length() as usize;
+        MultiChannelVad {
+            buf: Vec::with_capacity(cobras.len() * frame_length),
+            channel: Vec::with_capacity(frame_length),
+            cobras,
+            frame_length,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.cobras.len()
+    }
+
+    /// Buffers `interleaved` audio, which may be of any length, and returns
+    /// the per-channel confidences of any frames it completed.
+    pub fn push(&mut self, mut interleaved: &[i16]) -> Result<Vec<ChannelConfidences>> {
+        let block = self.frame_length * self.cobras.len();
+        let mut frames = Vec::with_capacity((self.buf.len() + interleaved.len()) / block);
+        if !self.buf.is_empty() {
+            let take = (block - self.buf.len()).min(interleaved.len());
+            self.buf.extend_from_slice(&interleaved[..take]);
+            interleaved = &interleaved[take..];
+            if self.buf.len() < block {
+                return Ok(frames);
+            }
+            let buf = mem::take(&mut self.buf);
+            frames.push(self.process_block(&buf)?);
+            self.buf = buf;
+            self.buf.clear();
+        }
+        let mut blocks = interleaved.chunks_exact(block);
+        for block in &mut blocks {
+            frames.push(self.process_block(block)?);
+        }
+        self.buf.extend_from_slice(blocks.remainder());
+        Ok(frames)
+    }
+
+    fn process_block(&mut self, block: &[i16]) -> Result<ChannelConfidences> {
+        let channels = self.cobras.len();
+        let mut confidences = Vec::with_capacity(channels);
+        for (i, cobra) in self.cobras.iter_mut().enumerate() {
+            self.channel.clear();
+            self.channel
+                .extend(block.iter().skip(i).step_by(channels).copied());
+            confidences.push(cobra.process(&self.channel)?);
+        }
+        Ok(ChannelConfidences {
+            best_channel: best_channel(&confidences),
+            confidences,
+        })
+    }
+
+    /// Drops any buffered audio.
+    pub fn reset(&mut self) {
+        self.buf.clear();
+    }
+
+    pub fn into_inner(self) -> Vec<Cobra> {
+        self.cobras
+    }
+}
+
+fn best_channel(confidences: &[Confidence]) -> usize {
+    confidences
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, c)| **c)
+        .map_or(0, |(i, _)| i)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_best_channel() {
+        let confidences = [0.2, 0.9, 0.4].map(Confidence::new);
+        assert_eq!(best_channel(&confidences), 1);
+    }
+}
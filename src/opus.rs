@@ -0,0 +1,86 @@
+use audiopus::coder::Decoder;
+use audiopus::packet::Packet;
+use audiopus::{Channels, MutSignals, SampleRate};
+
+use crate::resample::RubatoResampler;
+use crate::{Cobra, CobraStream, Confidence, Error, Result};
+
+/// Opus is decoded at its native rate and resampled down to Cobra's.
+const DECODE_RATE: u32 = 48000;
+
+/// Samples in the longest possible Opus packet, 120 ms at 48 kHz.
+const MAX_PACKET_SAMPLES: usize = 5760;
+
+/// Samples concealed for a packet lost before any was decoded, 20 ms at
+/// 48 kHz, the packet duration most senders use.
+const DEFAULT_PACKET_SAMPLES: usize = 960;
+
+impl From<audiopus::Error> for Error {
+    fn from(err: audiopus::Error) -> Self {
+        Error::UnsupportedFormat {
+            reason: format!("opus: {}", err),
+        }
+    }
+}
+
+/// Decodes Opus packets, as used by WebRTC, Discord and most VoIP stacks,
+/// and runs the audio through Cobra. Stereo packets are downmixed by the
+/// decoder.
+pub struct OpusStream {
+    decoder: Decoder,
+    stream: CobraStream,
+    pcm: Vec<i16>,
+}
+
+impl OpusStream {
+    pub fn new(cobra: Cobra) -> Result<Self> {
+        Self::with_stream(CobraStream::new(cobra))
+    }
+
+    /// Uses `stream`, e.g. with a smoother configured, replacing any
+    /// resampler it has.
+    pub fn with_stream(stream: CobraStream) -> Result<Self> {
+        let native = stream.cobra().sample_rate() as u32;
+        Ok(OpusStream {
+            decoder: Decoder::new(SampleRate::Hz48000, Channels::Mono)?,
+            stream: stream.with_resampler(RubatoResampler::new(DECODE_RATE, native)?),
+            pcm: vec![0; MAX_PACKET_SAMPLES],
+        })
+    }
+
+    /// Decodes a single Opus packet and returns the confidences of any frames
+    /// it completed.
+    pub fn push_packet(&mut self, packet: &[u8]) -> Result<Vec<Confidence>> {
+        let packet = Packet::try_from(packet)?;
+        self.decode(Some(packet), MAX_PACKET_SAMPLES)
+    }
+
+    /// Fills in for a lost packet using Opus' packet loss concealment, so
+    /// that timing stays aligned with the sender.
+    pub fn push_lost(&mut self) -> Result<Vec<Confidence>> {
+        // Concealment produces as much audio as there is room for, so limit
+        // it to the duration of the previous packet.
+        let samples = match self.decoder.last_packet_duration()? as usize {
+            0 => DEFAULT_PACKET_SAMPLES,
+            samples => samples.min(MAX_PACKET_SAMPLES),
+        };
+        self.decode(None, samples)
+    }
+
+    fn decode(&mut self, packet: Option<Packet<'_>>, max: usize) -> Result<Vec<Confidence>> {
+        let signals = MutSignals::try_from(&mut self.pcm[..max])?;
+        let samples = self.decoder.decode(packet, signals, false)?;
+        self.stream.push(&self.pcm[..samples])
+    }
+
+    /// Drops any buffered audio and resets the decoder state.
+    pub fn reset(&mut self) -> Result<()> {
+        self.decoder = Decoder::new(SampleRate::Hz48000, Channels::Mono)?;
+        self.stream.reset();
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> CobraStream {
+        self.stream
+    }
+}
@@ -0,0 +1,131 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::{AccessKey, Cobra, Result, lock};
+
+/// A fixed set of Cobra instances shared between threads, e.g. by a server
+/// handling many calls at once. Instances are either checked out for the
+/// length of a request with [`get`](Self::get), or picked by stream ID with
+/// [`for_key`](Self::for_key) so that a stream always lands on the same one.
+///
+/// Cobra carries context from one frame to the next, so a stream gets the
+/// most accurate results when it has an instance to itself.
+#[derive(Debug)]
+pub struct CobraPool {
+    cobras: Vec<Mutex<Cobra>>,
+    free: Mutex<Vec<usize>>,
+    returned: Condvar,
+}
+
+impl CobraPool {
+    /// Creates `size` instances.
+    pub fn new<K: Into<AccessKey>>(access_key: K, size: usize) -> Result<Self> {
+        let access_key = access_key.into();
+        let cobras = (0..size)
+            .map(|_| Cobra::new(access_key.clone()))
+            .collect::<Result<_>>()?;
+        Ok(Self::from_instances(cobras))
+    }
+
+    pub fn from_instances(cobras: Vec<Cobra>) -> Self {
+        assert!(!cobras.is_empty(), "at least one instance is required");
+        CobraPool {
+            free: Mutex::new((0..cobras.len()).rev().collect()),
+            cobras: cobras.into_iter().map(Mutex::new).collect(),
+            returned: Condvar::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cobras.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cobras.is_empty()
+    }
+
+    /// Number of instances not currently checked out.
+    pub fn available(&self) -> usize {
+        self.free_list().len()
+    }
+
+    /// Checks out an instance, waiting for one to be returned if they are
+    /// all in use. The instance goes back to the pool when the guard is
+    /// dropped.
+    pub fn get(&self) -> PooledCobra<'_> {
+        let mut free = self.free_list();
+        loop {
+            if let Some(index) = free.pop() {
+                return self.checkout(index);
+            }
+            free = self
+                .returned
+                .wait(free)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Checks out an instance if one is free.
+    pub fn try_get(&self) -> Option<PooledCobra<'_>> {
+        let index = self.free_list().pop()?;
+        Some(self.checkout(index))
+    }
+
+    /// Locks the instance that `key` hashes onto, waiting while another
+    /// caller is using it. The same key always maps to the same instance.
+    pub fn for_key<K: Hash + ?Sized>(&self, key: &K) -> MutexGuard<'_, Cobra> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let index = (hasher.finish() % self.cobras.len() as u64) as usize;
+        lock(&self.cobras[index])
+    }
+
+    pub fn into_inner(self) -> Vec<Cobra> {
+        self.cobras
+            .into_iter()
+            .map(|cobra| cobra.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .collect()
+    }
+
+    fn free_list(&self) -> MutexGuard<'_, Vec<usize>> {
+        lock(&self.free)
+    }
+
+    fn checkout(&self, index: usize) -> PooledCobra<'_> {
+        PooledCobra {
+            pool: self,
+            index,
+            cobra: lock(&self.cobras[index]),
+        }
+    }
+}
+
+/// An instance checked out of a [`CobraPool`], returned when dropped.
+#[derive(Debug)]
+pub struct PooledCobra<'a> {
+    pool: &'a CobraPool,
+    index: usize,
+    cobra: MutexGuard<'a, Cobra>,
+}
+
+impl Deref for PooledCobra<'_> {
+    type Target = Cobra;
+
+    fn deref(&self) -> &Cobra {
+        &self.cobra
+    }
+}
+
+impl DerefMut for PooledCobra<'_> {
+    fn deref_mut(&mut self) -> &mut Cobra {
+        &mut self.cobra
+    }
+}
+
+impl Drop for PooledCobra<'_> {
+    fn drop(&mut self) {
+        self.pool.free_list().push(self.index);
+        self.pool.returned.notify_one();
+    }
+}
@@ -0,0 +1,146 @@
+use crate::sample_rate;
+
+/// Conditions the audio of each frame before it reaches Cobra, e.g. to
+/// remove artifacts of cheap microphones.
+pub trait Preprocessor: Send {
+    fn process(&mut self, pcm: &mut [i16]);
+
+    fn reset(&mut self);
+}
+
+/// One-pole high-pass filter, removing DC offset and low-frequency rumble.
+#[derive(Debug, Clone)]
+pub struct HighPass {
+    alpha: f32,
+    prev_input: f32,
+    prev_output: f32,
+}
+
+impl HighPass {
+    /// Attenuates frequencies below `cutoff` Hz, e.g. 80 Hz.
+    pub fn new(cutoff: f32) -> Self {
+        let rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff);
+        let dt = 1.0 / sample_rate() as f32;
+        HighPass {
+            alpha: rc / (rc + dt),
+            prev_input: 0.0,
+            prev_output: 0.0,
+        }
+    }
+}
+
+impl Preprocessor for HighPass {
+    fn process(&mut self, pcm: &mut [i16]) {
+        for sample in pcm {
+            let input = *sample as f32;
+            let output = self.alpha * (self.prev_output + input - self.prev_input);
+            self.prev_input = input;
+            self.prev_output = output;
+            *sample = output.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.prev_input = 0.0;
+        self.prev_output = 0.0;
+    }
+}
+
+/// Automatic gain control, steering each frame towards a target RMS level.
+/// A limiter keeps amplified peaks from clipping.
+#[derive(Debug, Clone)]
+pub struct Agc {
+    target: f32,
+    max_gain: f32,
+    noise_floor: f32,
+    gain: f32,
+}
+
+impl Agc {
+    /// Targets an RMS level of `target_dbfs`, e.g. -20 dBFS.
+    pub fn new(target_dbfs: f32) -> Self {
+        Agc {
+            target: db_to_amplitude(target_dbfs),
+            max_gain: db_to_amplitude(30.0),
+            noise_floor: db_to_amplitude(-60.0),
+            gain: 1.0,
+        }
+    }
+
+    /// Caps the amplification applied to quiet input. Defaults to 30 dB.
+    pub fn with_max_gain(mut self, max_gain_db: f32) -> Self {
+        self.max_gain = db_to_amplitude(max_gain_db);
+        self
+    }
+
+    /// Frames quieter than `noise_floor_dbfs` are treated as silence and
+    /// leave the gain unchanged, so that background noise isn't boosted.
+    /// Defaults to -60 dBFS.
+    pub fn with_noise_floor(mut self, noise_floor_dbfs: f32) -> Self {
+        self.noise_floor = db_to_amplitude(noise_floor_dbfs);
+        self
+    }
+}
+
+impl Preprocessor for Agc {
+    fn process(&mut self, pcm: &mut [i16]) {
+        if pcm.is_empty() {
+            return;
+        }
+        let full_scale = -(i16::MIN as f32);
+        let sum: f32 = pcm.iter().map(|&s| (s as f32 / full_scale).powi(2)).sum();
+        let rms = (sum / pcm.len() as f32).sqrt();
+        if rms > self.noise_floor {
+            let desired = (self.target / rms).min(self.max_gain);
+            // Back off quickly when too loud, but recover slowly, so that
+            // the level doesn't pump between words.
+            let rate = if desired < self.gain { 0.5 } else { 0.05 };
+            self.gain += (desired - self.gain) * rate;
+        }
+        let peak = pcm.iter().map(|&s| s.unsigned_abs()).max().unwrap_or(0) as f32;
+        let gain = self.gain.min(i16::MAX as f32 / peak.max(1.0));
+        for sample in pcm {
+            *sample = (*sample as f32 * gain).round() as i16;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+}
+
+fn db_to_amplitude(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_high_pass_removes_dc_offset() {
+        let mut filter = HighPass::new(80.0);
+        let mut pcm = vec![1000; 16000];
+        filter.process(&mut pcm);
+        assert!(pcm[0] > 900);
+        assert!(pcm[pcm.len() - 1].abs() < 10);
+    }
+
+    #[test]
+    fn check_agc_steers_towards_target() {
+        let mut agc = Agc::new(-20.0);
+        let mut frame = Vec::new();
+        for _ in 0..200 {
+            frame = (0..512)
+                .map(|i| if i % 2 == 0 { 300 } else { -300 })
+                .collect();
+            agc.process(&mut frame);
+        }
+        // -20 dBFS is a tenth of full scale.
+        assert!((frame[0] as f32 - 3277.0).abs() < 50.0);
+
+        let mut loud = vec![i16::MAX / 2, i16::MIN / 2];
+        agc.process(&mut loud);
+        assert!(loud.iter().all(|s| s.unsigned_abs() <= i16::MAX as u16));
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::VecDeque;
+
+/// Keeps the most recent samples seen while idle, so that audio from just
+/// before a detected onset can be recovered.
+#[derive(Debug, Clone)]
+pub(crate) struct PreRoll {
+    buf: VecDeque<i16>,
+    capacity: usize,
+}
+
+impl PreRoll {
+    pub(crate) fn new(capacity: usize) -> Self {
+        PreRoll {
+            buf: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub(crate) fn push(&mut self, pcm: &[i16]) {
+        let pcm = &pcm[pcm.len().saturating_sub(self.capacity)..];
+        let overflow = (self.buf.len() + pcm.len()).saturating_sub(self.capacity);
+        self.buf.drain(..overflow);
+        self.buf.extend(pcm);
+    }
+
+    /// Moves the buffered samples, oldest first, to the end of `out`.
+    pub(crate) fn drain_into(&mut self, out: &mut Vec<i16>) {
+        out.extend(self.buf.drain(..));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.buf.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_pre_roll_keeps_latest_samples() {
+        let mut pre_roll = PreRoll::new(4);
+        pre_roll.push(&[1, 2, 3]);
+        pre_roll.push(&[4, 5]);
+        let mut out = vec![0];
+        pre_roll.drain_into(&mut out);
+        assert_eq!(out, [0, 2, 3, 4, 5]);
+        assert!(pre_roll.buf.is_empty());
+
+        pre_roll.push(&[1, 2, 3, 4, 5, 6]);
+        let mut out = Vec::new();
+        pre_roll.drain_into(&mut out);
+        assert_eq!(out, [3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn check_empty_pre_roll() {
+        let mut pre_roll = PreRoll::new(0);
+        pre_roll.push(&[1, 2]);
+        assert!(pre_roll.buf.is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI16, AtomicUsize, Ordering};
+
+use crate::frame_length;
+
+/// A fixed-size ring buffer of samples for handing audio from a real-time
+/// callback to a processing thread. Neither end locks or allocates, so
+/// [`FrameProducer::push`] is safe to call from an audio callback.
+#[derive(Debug)]
+pub struct FrameQueue {
+    samples: Box<[AtomicI16]>,
+    frame_length: usize,
+    // Total samples ever read and written. Both only grow, so the number
+    // buffered is always `written - read`.
+    read: AtomicUsize,
+    written: AtomicUsize,
+}
+
+impl FrameQueue {
+    /// A queue holding up to `frames` frames of Cobra's frame length.
+    pub fn new(frames: usize) -> Self {
+        Self::with_frame_length(frames, frame_length() as usize)
+    }
+
+    /// A queue holding up to `frames` frames of `frame_length` samples.
+    pub fn with_frame_length(frames: usize, frame_length: usize) -> Self {
+        assert!(frames > 0, "queue must hold at least one frame");
+        assert!(frame_length > 0, "frame length must be non-zero");
+        FrameQueue {
+            samples: (0..frames * frame_length)
+                .map(|_| AtomicI16::new(0))
+                .collect(),
+            frame_length,
+            read: AtomicUsize::new(0),
+            written: AtomicUsize::new(0),
+        }
+    }
+
+    /// Splits the queue into its writing and reading ends, which can be
+    /// moved to different threads.
+    pub fn split(self) -> (FrameProducer, FrameConsumer) {
+        let queue = Arc::new(self);
+        (
+            FrameProducer {
+                queue: Arc::clone(&queue),
+            },
+            FrameConsumer { queue },
+        )
+    }
+
+    fn capacity(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn len(&self) -> usize {
+        let read = self.read.load(Ordering::Acquire);
+        let written = self.written.load(Ordering::Acquire);
+        written.wrapping_sub(read)
+    }
+}
+
+/// The writing end of a [`FrameQueue`].
+#[derive(Debug)]
+pub struct FrameProducer {
+    queue: Arc<FrameQueue>,
+}
+
+impl FrameProducer {
+    /// Appends as much of `pcm` as fits, returning the number of samples
+    /// written. Chunks need not be frame-aligned.
+    pub fn push(&mut self, pcm: &[i16]) -> usize {
+        let queue = &*self.queue;
+        let written = queue.written.load(Ordering::Relaxed);
+        let count = pcm.len().min(queue.capacity() - queue.len());
+        for (i, &sample) in pcm[..count].iter().enumerate() {
+            let slot = written.wrapping_add(i) % queue.capacity();
+            queue.samples[slot].store(sample, Ordering::Relaxed);
+        }
+        queue
+            .written
+            .store(written.wrapping_add(count), Ordering::Release);
+        count
+    }
+
+    /// Number of samples that can be pushed without any being dropped.
+    pub fn free(&self) -> usize {
+        self.queue.capacity() - self.queue.len()
+    }
+}
+
+/// The reading end of a [`FrameQueue`].
+#[derive(Debug)]
+pub struct FrameConsumer {
+    queue: Arc<FrameQueue>,
+}
+
+impl FrameConsumer {
+    pub fn frame_length(&self) -> usize {
+        self.queue.frame_length
+    }
+
+    /// Number of complete frames waiting to be popped.
+    pub fn frames(&self) -> usize {
+        self.queue.len() / self.queue.frame_length
+    }
+
+    /// Copies the oldest complete frame into `frame`, which must hold
+    /// exactly `frame_length()` samples. Returns `false` if no complete
+    /// frame is buffered yet.
+    pub fn pop_frame(&mut self, frame: &mut [i16]) -> bool {
+        let queue = &*self.queue;
+        assert_eq!(frame.len(), queue.frame_length, "wrong frame length");
+        if queue.len() < queue.frame_length {
+            return false;
+        }
+        let read = queue.read.load(Ordering::Relaxed);
+        for (i, sample) in frame.iter_mut().enumerate() {
+            let slot = read.wrapping_add(i) % queue.capacity();
+            *sample = queue.samples[slot].load(Ordering::Relaxed);
+        }
+        queue
+            .read
+            .store(read.wrapping_add(queue.frame_length), Ordering::Release);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn check_push_and_pop_across_wrap() {
+        let (mut producer, mut consumer) = FrameQueue::with_frame_length(2, 3).split();
+        let mut frame = [0; 3];
+        assert!(!consumer.pop_frame(&mut frame));
+        assert_eq!(producer.push(&[1, 2, 3, 4]), 4);
+        assert!(consumer.pop_frame(&mut frame));
+        assert_eq!(frame, [1, 2, 3]);
+        assert!(!consumer.pop_frame(&mut frame));
+        // Only five samples fit, the last of which wraps around.
+        assert_eq!(producer.push(&[5, 6, 7, 8, 9, 10]), 5);
+        assert_eq!(producer.free(), 0);
+        assert_eq!(consumer.frames(), 2);
+        assert!(consumer.pop_frame(&mut frame));
+        assert_eq!(frame, [4, 5, 6]);
+        assert!(consumer.pop_frame(&mut frame));
+        assert_eq!(frame, [7, 8, 9]);
+        assert_eq!(producer.free(), 6);
+    }
+
+    #[test]
+    fn check_across_threads() {
+        let (mut producer, mut consumer) = FrameQueue::with_frame_length(4, 16).split();
+        let samples: Vec<i16> = (0..4000).map(|i| i as i16).collect();
+        let expected = samples.clone();
+        let writer = thread::spawn(move || {
+            let mut pcm = &samples[..];
+            while !pcm.is_empty() {
+                let n = producer.push(&pcm[..pcm.len().min(10)]);
+                pcm = &pcm[n..];
+                thread::yield_now();
+            }
+        });
+        let mut received = Vec::new();
+        let mut frame = [0; 16];
+        while received.len() < expected.len() {
+            if consumer.pop_frame(&mut frame) {
+                received.extend_from_slice(&frame);
+            } else {
+                thread::yield_now();
+            }
+        }
+        writer.join().unwrap();
+        assert_eq!(received, expected);
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::{self, Read};
+use std::mem;
+
+use crate::sample::decode_bytes;
+use crate::{Endianness, Result, SampleWidth, frame_length};
+
+/// The frame being read by a [`FrameReader`] or its async counterpart: raw
+/// bytes are read into it until it's full or the input ends, then decoded.
+pub(crate) struct FrameBuffer {
+    width: SampleWidth,
+    endianness: Endianness,
+    frame_length: usize,
+    bytes: Vec<u8>,
+    filled: usize,
+    frame: Vec<i16>,
+}
+
+impl FrameBuffer {
+    pub(crate) fn new(frame_length: usize) -> Self {
+        FrameBuffer {
+            width: SampleWidth::I16,
+            endianness: Endianness::Little,
+            frame_length,
+            bytes: vec![0; frame_length * 2],
+            filled: 0,
+            frame: Vec::with_capacity(frame_length),
+        }
+    }
+
+    pub(crate) fn set_sample_width(&mut self, width: SampleWidth) {
+        self.bytes = vec![0; self.frame_length * width.bytes()];
+        self.filled = 0;
+        self.width = width;
+    }
+
+    pub(crate) fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.filled == self.bytes.len()
+    }
+
+    /// Where the next read should go.
+    pub(crate) fn unfilled(&mut self) -> &mut [u8] {
+        &mut self.bytes[self.filled..]
+    }
+
+    /// Marks `n` more bytes as read.
+    pub(crate) fn advance(&mut self, n: usize) {
+        self.filled += n;
+    }
+
+    /// Decodes what has been read, which is a partial frame at the end of
+    /// the input, and starts over. Returns `None` if not even a whole sample
+    /// was read, as trailing bytes that cannot form one are dropped.
+    pub(crate) fn take_frame(&mut self) -> Option<&[i16]> {
+        let filled = mem::take(&mut self.filled);
+        if filled < self.width.bytes() {
+            return None;
+        }
+        self.frame.clear();
+        decode_bytes(
+            &self.bytes[..filled],
+            self.width,
+            self.endianness,
+            &mut self.frame,
+        );
+        Some(&self.frame)
+    }
+}
+
+/// Reads PCM from any reader, one frame at a time.
+pub struct FrameReader<R> {
+    reader: R,
+    buf: FrameBuffer,
+}
+
+impl<R: Read> FrameReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_frame_length(reader, frame_length() as usize)
+    }
+
+    /// Reads frames of `frame_length` samples instead of Cobra's.
+    pub fn with_frame_length(reader: R, frame_length: usize) -> Self {
+        FrameReader {
+            reader,
+            buf: FrameBuffer::new(frame_length),
+        }
+    }
+
+    /// Reads samples of the given width, which are scaled to 16 bits.
+    /// Defaults to [`SampleWidth::I16`].
+    pub fn with_sample_width(mut self, width: SampleWidth) -> Self {
+        self.buf.set_sample_width(width);
+        self
+    }
+
+    /// Reads samples in the given byte order. Defaults to
+    /// [`Endianness::Little`].
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.buf.set_endianness(endianness);
+        self
+    }
+
+    /// Reads the next frame, retrying short reads until it is complete.
+    /// At the end of the input, a final partial frame is returned as is, to
+    /// be processed with [`Cobra::process_partial`](crate::Cobra::process_partial),
+    /// followed by `None`.
+    pub fn read_frame(&mut self) -> Result<Option<&[i16]>> {
+        while !self.buf.is_full() {
+            match self.reader.read(self.buf.unfilled()) {
+                Ok(0) => break,
+                Ok(n) => self.buf.advance(n),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(self.buf.take_frame())
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = Result<Vec<i16>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame()
+            .map(|frame| frame.map(<[i16]>::to_vec))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns at most `chunk` bytes per read, like a pipe or socket might.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.chunk).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn check_frames_across_short_reads() {
+        let samples: Vec<i16> = (0..7).map(|i| i * 1000 - 3000).collect();
+        let mut data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        data.push(0xff);
+        let reader = Trickle {
+            data: &data,
+            chunk: 3,
+        };
+        let frames: Vec<Vec<i16>> = FrameReader::with_frame_length(reader, 3)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(frames, [&samples[0..3], &samples[3..6], &samples[6..]]);
+    }
+}
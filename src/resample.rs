@@ -0,0 +1,223 @@
+#[cfg(feature = "resample")]
+use rubato::{FftFixedIn, Resampler as _};
+
+#[cfg(feature = "resample")]
+use crate::{Error, Result};
+
+/// Converts mono 16-bit audio between sample rates, fixed at construction.
+/// Used by [`CobraStream::with_resampler`](crate::CobraStream::with_resampler).
+pub trait Resampler: Send {
+    /// Sample rate of the audio passed to [`process`](Self::process), in Hz.
+    fn input_rate(&self) -> u32;
+
+    /// Resamples `pcm`, appending whatever output is ready to `out`.
+    fn process(&mut self, pcm: &[i16], out: &mut Vec<i16>);
+
+    /// Pushes out any audio still held back, e.g. at the end of a stream.
+    fn flush(&mut self, out: &mut Vec<i16>);
+
+    fn reset(&mut self);
+}
+
+/// Linear interpolation between neighbouring samples. Cheap enough for
+/// embedded targets, but does not low-pass filter, so downsampling aliases
+/// content above the output's Nyquist frequency.
+#[derive(Debug, Clone)]
+pub struct LinearResampler {
+    input_rate: u32,
+    step: f64,
+    /// Time of the next output sample, in input samples since `last`.
+    t: f64,
+    last: Option<i16>,
+}
+
+impl LinearResampler {
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        assert!(
+            input_rate > 0 && output_rate > 0,
+            "sample rates must be positive"
+        );
+        LinearResampler {
+            input_rate,
+            step: input_rate as f64 / output_rate as f64,
+            t: 0.0,
+            last: None,
+        }
+    }
+}
+
+impl Resampler for LinearResampler {
+    fn input_rate(&self) -> u32 {
+        self.input_rate
+    }
+
+    fn process(&mut self, mut pcm: &[i16], out: &mut Vec<i16>) {
+        let last = match self.last {
+            Some(last) => last,
+            None => match pcm.split_first() {
+                Some((&first, rest)) => {
+                    pcm = rest;
+                    first
+                }
+                None => return,
+            },
+        };
+        let sample = |i: usize| if i == 0 { last } else { pcm[i - 1] };
+        let n = pcm.len() as f64;
+        while self.t < n {
+            let i = self.t as usize;
+            let frac = self.t - i as f64;
+            let (a, b) = (sample(i) as f64, sample(i + 1) as f64);
+            out.push((a + (b - a) * frac).round() as i16);
+            self.t += self.step;
+        }
+        self.t -= n;
+        self.last = Some(sample(pcm.len()));
+    }
+
+    fn flush(&mut self, _out: &mut Vec<i16>) {
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.t = 0.0;
+        self.last = None;
+    }
+}
+
+/// Number of input samples resampled at a time.
+#[cfg(feature = "resample")]
+const CHUNK_SIZE: usize = 1024;
+
+/// High-quality FFT-based resampling using `rubato`.
+#[cfg(feature = "resample")]
+pub struct RubatoResampler {
+    inner: FftFixedIn<f32>,
+    input: Vec<f32>,
+    input_rate: u64,
+    output_rate: u64,
+    /// Samples fed in and emitted so far, used to trim the flushed output.
+    consumed: u64,
+    produced: u64,
+    /// Leading output samples still to drop to compensate for the filter's
+    /// delay.
+    delay: usize,
+}
+
+#[cfg(feature = "resample")]
+impl RubatoResampler {
+    pub fn new(input_rate: u32, output_rate: u32) -> Result<Self> {
+        let inner = FftFixedIn::new(input_rate as usize, output_rate as usize, CHUNK_SIZE, 2, 1)
+            .map_err(|err| Error::UnsupportedFormat {
+                reason: format!("cannot resample from {} Hz: {}", input_rate, err),
+            })?;
+        Ok(RubatoResampler {
+            delay: inner.output_delay(),
+            inner,
+            input: Vec::with_capacity(CHUNK_SIZE),
+            input_rate: input_rate as u64,
+            output_rate: output_rate as u64,
+            consumed: 0,
+            produced: 0,
+        })
+    }
+
+    fn emit(&mut self, samples: &[f32], limit: u64, out: &mut Vec<i16>) {
+        let skip = self.delay.min(samples.len());
+        self.delay -= skip;
+        let take = (limit - self.produced).min((samples.len() - skip) as u64) as usize;
+        self.produced += take as u64;
+        out.extend(samples[skip..skip + take].iter().map(|&s| {
+            (s * -(i16::MIN as f32))
+                .round()
+                .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+        }));
+    }
+}
+
+#[cfg(feature = "resample")]
+impl Resampler for RubatoResampler {
+    fn input_rate(&self) -> u32 {
+        self.input_rate as u32
+    }
+
+    fn process(&mut self, pcm: &[i16], out: &mut Vec<i16>) {
+        self.consumed += pcm.len() as u64;
+        self.input
+            .extend(pcm.iter().map(|&s| s as f32 / -(i16::MIN as f32)));
+        loop {
+            let needed = self.inner.input_frames_next();
+            if self.input.len() < needed {
+                return;
+            }
+            let output = self
+                .inner
+                .process(&[&self.input[..needed]], None)
+                .expect("input is sized as requested by the resampler");
+            self.input.drain(..needed);
+            self.emit(&output[0], u64::MAX, out);
+        }
+    }
+
+    fn flush(&mut self, out: &mut Vec<i16>) {
+        let expected = self.consumed * self.output_rate / self.input_rate;
+        while self.produced < expected {
+            let output = if self.input.is_empty() {
+                self.inner.process_partial(None::<&[&[f32]]>, None)
+            } else {
+                self.inner.process_partial(Some(&[&self.input[..]]), None)
+            }
+            .expect("partial input never exceeds what the resampler requests");
+            self.input.clear();
+            self.emit(&output[0], expected, out);
+        }
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        self.input.clear();
+        self.consumed = 0;
+        self.produced = 0;
+        self.delay = self.inner.output_delay();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_linear_upsampling() {
+        let mut resampler = LinearResampler::new(8000, 16000);
+        let mut out = Vec::new();
+        resampler.process(&[0, 100], &mut out);
+        resampler.process(&[200], &mut out);
+        assert_eq!(out, [0, 50, 100, 150]);
+    }
+
+    #[test]
+    fn check_linear_downsampling() {
+        let mut resampler = LinearResampler::new(48000, 16000);
+        let pcm: Vec<i16> = (0..30).collect();
+        let mut out = Vec::new();
+        for chunk in pcm.chunks(4) {
+            resampler.process(chunk, &mut out);
+        }
+        assert_eq!(out, [0, 3, 6, 9, 12, 15, 18, 21, 24, 27]);
+    }
+
+    #[cfg(feature = "resample")]
+    #[test]
+    fn check_resampled_length() {
+        let mut resampler = RubatoResampler::new(48000, 16000).unwrap();
+        let pcm: Vec<i16> = (0..4800).map(|i| ((i % 48) * 500) as i16).collect();
+        let mut out = Vec::new();
+        for chunk in pcm.chunks(333) {
+            resampler.process(chunk, &mut out);
+        }
+        assert!(out.len() < 1600);
+        resampler.flush(&mut out);
+        assert_eq!(out.len(), 1600);
+    }
+}
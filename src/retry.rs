@@ -0,0 +1,131 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::Result;
+
+/// Exponential backoff applied to operations that fail with a transient
+/// error (see [`Error::is_transient`](crate::Error::is_transient)).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// The delay to wait after the given (zero-based) failed attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(attempt.min(i32::MAX as u32) as i32);
+        let secs = self.initial_backoff.as_secs_f64() * factor;
+        if secs >= self.max_backoff.as_secs_f64() {
+            self.max_backoff
+        } else {
+            Duration::from_secs_f64(secs)
+        }
+    }
+
+    /// Runs `f` until it succeeds, fails with a non-transient error, or the
+    /// attempts are exhausted. The last error is returned on failure.
+    pub fn run<T, F: FnMut() -> Result<T>>(&self, mut f: F) -> Result<T> {
+        let mut attempt = 0;
+        loop {
+            match f() {
+                Err(err) if err.is_transient() && attempt + 1 < self.max_attempts => {
+                    thread::sleep(self.backoff(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Error;
+
+    fn throttled() -> Error {
+        Error::ActivationThrottled {
+            messages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn check_backoff_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            multiplier: 2.0,
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(350));
+        assert_eq!(policy.backoff(30), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn check_run_retries_transient_errors() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..RetryPolicy::new(3)
+        };
+        let mut calls = 0;
+        let result = policy.run(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(throttled())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let result: Result<()> = policy.run(|| {
+            calls += 1;
+            Err(throttled())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn check_run_stops_on_permanent_errors() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..RetryPolicy::new(5)
+        };
+        let mut calls = 0;
+        let result: Result<()> = policy.run(|| {
+            calls += 1;
+            Err(Error::NullValue)
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,150 @@
+/// A PCM sample format that can be scaled to the 16-bit samples Cobra
+/// expects. Integer formats are scaled by bit shifting, and floating point
+/// samples are expected in `-1.0..=1.0` and clamped to that range.
+pub trait IntoPcm16: Copy {
+    fn into_pcm16(self) -> i16;
+}
+
+impl IntoPcm16 for i16 {
+    fn into_pcm16(self) -> i16 {
+        self
+    }
+}
+
+impl IntoPcm16 for i8 {
+    fn into_pcm16(self) -> i16 {
+        (self as i16) << 8
+    }
+}
+
+/// Unsigned 8-bit PCM, as used by 8-bit WAV files, centered on 128.
+impl IntoPcm16 for u8 {
+    fn into_pcm16(self) -> i16 {
+        ((self as i16) - 128) << 8
+    }
+}
+
+impl IntoPcm16 for i32 {
+    fn into_pcm16(self) -> i16 {
+        (self >> 16) as i16
+    }
+}
+
+impl IntoPcm16 for f32 {
+    fn into_pcm16(self) -> i16 {
+        (self as f64).into_pcm16()
+    }
+}
+
+impl IntoPcm16 for f64 {
+    fn into_pcm16(self) -> i16 {
+        // NaN maps to silence rather than to an arbitrary extreme.
+        if self.is_nan() {
+            return 0;
+        }
+        (self * 32768.0)
+            .round()
+            .clamp(i16::MIN as f64, i16::MAX as f64) as i16
+    }
+}
+
+/// Byte order of samples in byte input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endianness {
+    #[default]
+    Little,
+    /// Network byte order, as delivered by some telephony and embedded
+    /// sources.
+    Big,
+}
+
+/// Size of the samples in packed byte input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SampleWidth {
+    /// 16-bit samples, taking two bytes each.
+    #[default]
+    I16,
+    /// Packed 24-bit samples, taking three bytes each, as produced by many
+    /// USB audio interfaces and broadcast WAV files.
+    I24,
+}
+
+impl SampleWidth {
+    pub fn bytes(self) -> usize {
+        match self {
+            SampleWidth::I16 => 2,
+            SampleWidth::I24 => 3,
+        }
+    }
+}
+
+/// Decodes packed samples into `out`, ignoring any trailing incomplete
+/// sample. Only the two most significant bytes of wider samples are kept.
+pub(crate) fn decode_bytes(
+    bytes: &[u8],
+    width: SampleWidth,
+    endianness: Endianness,
+    out: &mut Vec<i16>,
+) {
+    let samples = bytes.chunks_exact(width.bytes());
+    match (width, endianness) {
+        (SampleWidth::I16, Endianness::Little) => {
+            out.extend(samples.map(|b| i16::from_le_bytes([b[0], b[1]])))
+        }
+        (SampleWidth::I16, Endianness::Big) => {
+            out.extend(samples.map(|b| i16::from_be_bytes([b[0], b[1]])))
+        }
+        (SampleWidth::I24, Endianness::Little) => {
+            out.extend(samples.map(|b| i16::from_le_bytes([b[1], b[2]])))
+        }
+        (SampleWidth::I24, Endianness::Big) => {
+            out.extend(samples.map(|b| i16::from_be_bytes([b[0], b[1]])))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_into_pcm16() {
+        assert_eq!(i8::MIN.into_pcm16(), i16::MIN);
+        assert_eq!(0u8.into_pcm16(), i16::MIN);
+        assert_eq!(128u8.into_pcm16(), 0);
+        assert_eq!(255u8.into_pcm16(), 127 << 8);
+        assert_eq!(i32::MAX.into_pcm16(), i16::MAX);
+        assert_eq!(i32::MIN.into_pcm16(), i16::MIN);
+        assert_eq!((-1.0f32).into_pcm16(), i16::MIN);
+        assert_eq!(1.0f32.into_pcm16(), i16::MAX);
+        assert_eq!(0.5f64.into_pcm16(), 16384);
+        assert_eq!(2.0f64.into_pcm16(), i16::MAX);
+        assert_eq!(f64::NAN.into_pcm16(), 0);
+    }
+
+    #[test]
+    fn check_decode_bytes() {
+        let mut out = Vec::new();
+        let le = Endianness::Little;
+        decode_bytes(
+            &[0x34, 0x12, 0xff, 0xff, 0x01],
+            SampleWidth::I16,
+            le,
+            &mut out,
+        );
+        assert_eq!(out, [0x1234, -1]);
+        out.clear();
+        decode_bytes(
+            &[0x56, 0x34, 0x12, 0x00, 0x00, 0x80, 0xff],
+            SampleWidth::I24,
+            le,
+            &mut out,
+        );
+        assert_eq!(out, [0x1234, i16::MIN]);
+        out.clear();
+        let be = Endianness::Big;
+        decode_bytes(&[0x12, 0x34], SampleWidth::I16, be, &mut out);
+        decode_bytes(&[0x80, 0x00, 0x00], SampleWidth::I24, be, &mut out);
+        assert_eq!(out, [0x1234, i16::MIN]);
+    }
+}
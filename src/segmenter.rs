@@ -0,0 +1,356 @@
+use std::mem;
+use std::time::Duration;
+
+use crate::detector::HysteresisDetector;
+use crate::preroll::PreRoll;
+use crate::{
+    Cobra, Confidence, Result, duration_to_frames, duration_to_samples, frames_to_duration,
+    samples_to_duration,
+};
+
+/// A contiguous run of speech, timed relative to the first frame pushed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub start: Duration,
+    pub end: Duration,
+    pub peak_confidence: Confidence,
+    /// The segment's audio, when frames were fed with
+    /// [`SpeechSegmenter::push_frame`]. Starts at `audio_start`, which is
+    /// earlier than `start` when a pre-roll is configured.
+    pub audio: Vec<i16>,
+    pub audio_start: Duration,
+}
+
+impl Segment {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OpenSegment {
+    start: u64,
+    confidences: Vec<Confidence>,
+    audio: Vec<i16>,
+    /// Number of pre-roll samples at the beginning of `audio`.
+    lead: usize,
+    /// Number of trailing non-speech frames, which are dropped unless speech
+    /// resumes within the configured maximum gap.
+    gap: usize,
+}
+
+impl OpenSegment {
+    fn peak(&self) -> Confidence {
+        self.confidences.iter().copied().max().unwrap_or_default()
+    }
+
+    fn into_segment(self) -> Segment {
+        let start = frames_to_duration(self.start);
+        Segment {
+            start,
+            end: frames_to_duration(self.start + self.confidences.len() as u64),
+            peak_confidence: self.peak(),
+            audio: self.audio,
+            audio_start: start.saturating_sub(samples_to_duration(self.lead as u64)),
+        }
+    }
+
+    /// Splits off the frames before the lowest confidence among the last
+    /// `window` frames, leaving the rest open.
+    fn split(&mut self, window: usize) -> Segment {
+        let len = self.confidences.len();
+        let from = len - window.clamp(1, len - 1);
+        let (offset, _) = self.confidences[from..]
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, c)| **c)
+            .unwrap();
+        let at = from + offset;
+        let rest_confidences = self.confidences.split_off(at);
+        let rest_audio = if self.audio.is_empty() {
+            Vec::new()
+        } else {
+            let frame_length = (self.audio.len() - self.lead) / len;
+            self.audio.split_off(self.lead + at * frame_length)
+        };
+        let head = OpenSegment {
+            start: self.start,
+            confidences: mem::replace(&mut self.confidences, rest_confidences),
+            audio: mem::replace(&mut self.audio, rest_audio),
+            lead: mem::take(&mut self.lead),
+            gap: 0,
+        };
+        self.start += at as u64;
+        head.into_segment()
+    }
+}
+
+/// Groups per-frame confidences into speech [`Segment`]s.
+#[derive(Debug, Clone)]
+pub struct SpeechSegmenter {
+    detector: HysteresisDetector,
+    min_speech_frames: u64,
+    max_segment_frames: Option<u64>,
+    max_gap_frames: u64,
+    pre_roll: PreRoll,
+    frames: u64,
+    open: Option<OpenSegment>,
+}
+
+impl SpeechSegmenter {
+    pub fn new(threshold: f32) -> Self {
+        Self::with_detector(HysteresisDetector::new(threshold, threshold))
+    }
+
+    /// Uses `detector` to decide which frames are speech.
+    pub fn with_detector(detector: HysteresisDetector) -> Self {
+        SpeechSegmenter {
+            detector,
+            min_speech_frames: 0,
+            max_segment_frames: None,
+            max_gap_frames: 1,
+            pre_roll: PreRoll::new(0),
+            frames: 0,
+            open: None,
+        }
+    }
+
+    /// Drops segments shorter than `min`, such as clicks and coughs.
+    pub fn with_min_speech_duration(mut self, min: Duration) -> Self {
+        self.min_speech_frames = duration_to_frames(min);
+        self
+    }
+
+    /// Splits speech runs longer than `max` into contiguous segments, e.g. to
+    /// respect the input limit of a downstream ASR engine. Each split happens
+    /// at the lowest-confidence frame within the last quarter of the limit.
+    pub fn with_max_segment_duration(mut self, max: Duration) -> Self {
+        self.max_segment_frames = Some(duration_to_frames(max).max(2));
+        self
+    }
+
+    /// Merges segments separated by less than `max_gap` of silence into a
+    /// single segment that includes the gap. Segments are emitted only once
+    /// the silence after them reaches `max_gap`.
+    pub fn with_gap_bridging(mut self, max_gap: Duration) -> Self {
+        self.max_gap_frames = duration_to_frames(max_gap).max(1);
+        self
+    }
+
+    /// Prepends up to `pre_roll` of the audio preceding each onset to the
+    /// segment's audio, since detection typically lags the actual start of
+    /// speech. Only applies to frames fed with [`push_frame`](Self::push_frame).
+    pub fn with_pre_roll(mut self, pre_roll: Duration) -> Self {
+        self.pre_roll = PreRoll::new(duration_to_samples(pre_roll) as usize);
+        self
+    }
+
+    /// Feeds the confidence of the next frame, returning a segment once it
+    /// has ended.
+    pub fn push(&mut self, confidence: Confidence) -> Option<Segment> {
+        self.advance(confidence, None)
+    }
+
+    /// Like [`push`](Self::push), but also records the frame's audio so that
+    /// it is included in the emitted segment.
+    pub fn push_frame(&mut self, pcm: &[i16], confidence: Confidence) -> Option<Segment> {
+        self.advance(confidence, Some(pcm))
+    }
+
+    fn advance(&mut self, confidence: Confidence, pcm: Option<&[i16]>) -> Option<Segment> {
+        let frame = self.frames;
+        self.frames += 1;
+        if self.detector.update(confidence) {
+            let pre_roll = &mut self.pre_roll;
+            let open = self.open.get_or_insert_with(|| {
+                let mut audio = Vec::new();
+                if pcm.is_some() {
+                    pre_roll.drain_into(&mut audio);
+                }
+                OpenSegment {
+                    start: frame,
+                    confidences: Vec::new(),
+                    lead: audio.len(),
+                    audio,
+                    gap: 0,
+                }
+            });
+            // Speech resumed, so any silence since the last speech frame is
+            // bridged into this segment.
+            open.gap = 0;
+            open.confidences.push(confidence);
+            if let Some(pcm) = pcm {
+                open.audio.extend_from_slice(pcm);
+            }
+            match self.max_segment_frames {
+                Some(max) if open.confidences.len() as u64 >= max => {
+                    Some(open.split(max.div_ceil(4) as usize))
+                }
+                _ => None,
+            }
+        } else {
+            let Some(open) = &mut self.open else {
+                if let Some(pcm) = pcm {
+                    self.pre_roll.push(pcm);
+                }
+                return None;
+            };
+            open.gap += 1;
+            open.confidences.push(confidence);
+            if let Some(pcm) = pcm {
+                open.audio.extend_from_slice(pcm);
+            }
+            if (open.gap as u64) < self.max_gap_frames {
+                return None;
+            }
+
+            self.close()
+        }
+    }
+
+    /// Emits the open segment, if any, dropping its trailing gap frames.
+    fn close(&mut self) -> Option<Segment> {
+        let mut open = self.open.take()?;
+        let speech_frames = open.confidences.len() - open.gap;
+        if !open.audio.is_empty() {
+            let frame_length = (open.audio.len() - open.lead) / open.confidences.len();
+            let speech_samples = open.audio.len() - open.gap * frame_length;
+            self.pre_roll.push(&open.audio[speech_samples..]);
+            open.audio.truncate(speech_samples);
+        }
+        open.confidences.truncate(speech_frames);
+        if (speech_frames as u64) < self.min_speech_frames {
+            return None;
+        }
+        Some(open.into_segment())
+    }
+
+    /// Closes the segment still open at the end of a stream, which would
+    /// otherwise never be emitted.
+    pub fn finish(&mut self) -> Option<Segment> {
+        self.detector.reset();
+        self.close()
+    }
+
+    /// Runs `pcm` through `cobra` and feeds the resulting confidence along
+    /// with the audio.
+    pub fn process(&mut self, cobra: &mut Cobra, pcm: &[i16]) -> Result<Option<Segment>> {
+        let confidence = cobra.process(pcm)?;
+        Ok(self.push_frame(pcm, confidence))
+    }
+
+    /// Whether a segment is currently open.
+    pub fn is_speech(&self) -> bool {
+        self.open.is_some()
+    }
+
+    pub fn reset(&mut self) {
+        self.detector.reset();
+        self.pre_roll.clear();
+        self.frames = 0;
+        self.open = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn push_all(segmenter: &mut SpeechSegmenter, confidences: &[f32]) -> Vec<Segment> {
+        confidences
+            .iter()
+            .filter_map(|&c| segmenter.push(Confidence::new(c)))
+            .collect()
+    }
+
+    fn segment(start: u64, end: u64, peak: f32) -> Segment {
+        Segment {
+            start: frames_to_duration(start),
+            end: frames_to_duration(end),
+            peak_confidence: Confidence::new(peak),
+            audio: Vec::new(),
+            audio_start: frames_to_duration(start),
+        }
+    }
+
+    #[test]
+    fn check_segments_are_timestamped() {
+        let mut segmenter = SpeechSegmenter::new(0.5);
+        let segments = push_all(&mut segmenter, &[0.1, 0.6, 0.9, 0.7, 0.2, 0.1, 0.8, 0.3]);
+        assert_eq!(segments, [segment(1, 4, 0.9), segment(6, 7, 0.8)]);
+        assert_eq!(segments[0].duration(), frames_to_duration(3));
+    }
+
+    #[test]
+    fn check_segmenter_with_hysteresis() {
+        let mut segmenter = SpeechSegmenter::with_detector(HysteresisDetector::new(0.7, 0.3));
+        let segments = push_all(&mut segmenter, &[0.5, 0.8, 0.4, 0.5, 0.2]);
+        assert_eq!(segments, [segment(1, 4, 0.8)]);
+    }
+
+    #[test]
+    fn check_short_segments_are_dropped() {
+        let mut segmenter =
+            SpeechSegmenter::new(0.5).with_min_speech_duration(frames_to_duration(2));
+        let segments = push_all(&mut segmenter, &[0.9, 0.1, 0.9, 0.9, 0.1]);
+        assert_eq!(segments, [segment(2, 4, 0.9)]);
+    }
+
+    #[test]
+    fn check_long_segments_are_split() {
+        let mut segmenter =
+            SpeechSegmenter::new(0.5).with_max_segment_duration(frames_to_duration(8));
+        let segments = push_all(
+            &mut segmenter,
+            &[0.9, 0.9, 0.9, 0.9, 0.9, 0.9, 0.6, 0.9, 0.9, 0.9, 0.1],
+        );
+        assert_eq!(segments, [segment(0, 6, 0.9), segment(6, 10, 0.9)]);
+    }
+
+    #[test]
+    fn check_short_gaps_are_bridged() {
+        let mut segmenter = SpeechSegmenter::new(0.5)
+            .with_gap_bridging(frames_to_duration(3))
+            .with_min_speech_duration(frames_to_duration(4));
+        let segments = push_all(
+            &mut segmenter,
+            &[0.9, 0.9, 0.1, 0.1, 0.8, 0.1, 0.1, 0.1, 0.9, 0.1, 0.1, 0.1],
+        );
+        assert_eq!(segments, [segment(0, 5, 0.9)]);
+    }
+
+    #[test]
+    fn check_pre_roll_is_prepended() {
+        let frame_length = crate::frame_length() as usize;
+        let mut segmenter = SpeechSegmenter::new(0.5).with_pre_roll(frames_to_duration(1));
+        let frames: Vec<Vec<i16>> = (0..4).map(|i| vec![i; frame_length]).collect();
+        let confidences = [0.1, 0.1, 0.9, 0.1];
+        let segments: Vec<Segment> = frames
+            .iter()
+            .zip(confidences)
+            .filter_map(|(pcm, c)| segmenter.push_frame(pcm, Confidence::new(c)))
+            .collect();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].start, frames_to_duration(2));
+        assert_eq!(segments[0].audio_start, frames_to_duration(1));
+        assert_eq!(segments[0].audio.len(), 2 * frame_length);
+        assert_eq!(segments[0].audio[0], 1);
+        assert_eq!(segments[0].audio[frame_length], 2);
+    }
+
+    #[test]
+    fn check_open_segment_is_not_emitted() {
+        let mut segmenter = SpeechSegmenter::new(0.5);
+        assert!(push_all(&mut segmenter, &[0.9, 0.9]).is_empty());
+        assert!(segmenter.is_speech());
+    }
+
+    #[test]
+    fn check_finish_emits_open_segment() {
+        let mut segmenter = SpeechSegmenter::new(0.5).with_gap_bridging(frames_to_duration(3));
+        assert!(push_all(&mut segmenter, &[0.1, 0.9, 0.8, 0.1]).is_empty());
+        assert_eq!(segmenter.finish(), Some(segment(1, 3, 0.9)));
+        assert!(!segmenter.is_speech());
+        assert_eq!(segmenter.finish(), None);
+    }
+}
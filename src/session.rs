@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+use std::mem;
+use std::time::Duration;
+
+use crate::events::{Event, VadEvents};
+use crate::{Cobra, CobraStream, Instant, Padding, Result, samples_to_duration};
+
+/// Runs audio through a [`CobraStream`] and a [`VadEvents`], keeping track of
+/// the absolute sample position so that events are timed from the start of
+/// the session (plus an optional origin) rather than in frames. Padding the
+/// final frame doesn't push the end of the session past the audio pushed.
+pub struct VadSession {
+    stream: CobraStream,
+    events: VadEvents,
+    origin: Duration,
+    samples: u64,
+}
+
+impl VadSession {
+    pub fn new(cobra: Cobra, threshold: f32) -> Self {
+        Self::with_events(CobraStream::new(cobra), VadEvents::new(threshold))
+    }
+
+    pub fn with_events(stream: CobraStream, events: VadEvents) -> Self {
+        VadSession {
+            stream,
+            events,
+            origin: Duration::ZERO,
+            samples: 0,
+        }
+    }
+
+    /// Offsets every timestamp by `origin`, e.g. the time into a call at
+    /// which the session was started.
+    pub fn with_origin(mut self, origin: Duration) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Processes `pcm`, which may be of any length, and returns the events
+    /// triggered by the frames it completed.
+    pub fn push(&mut self, pcm: &[i16]) -> Result<Vec<Event>> {
+        let fed = self.stream.fed();
+        let confidences = self.stream.push(pcm)?;
+        self.samples += self.stream.fed() - fed;
+        let origin = self.origin;
+        Ok(confidences
+            .into_iter()
+            .filter_map(|confidence| self.events.push(confidence))
+            .map(|event| event.offset(origin))
+            .collect())
+    }
+
+    /// Processes any buffered audio as a zero-padded final frame and ends
+    /// speech still in progress, returning the remaining events.
+    pub fn finish(&mut self) -> Result<Vec<Event>> {
+        let mut events = Vec::new();
+        let fed = self.stream.fed();
+        let confidences = self.stream.finish(Padding::Zero)?;
+        self.samples += self.stream.fed() - fed;
+        for confidence in confidences {
+            events.extend(self.events.push(confidence));
+        }
+        events.extend(self.events.finish());
+        let end = samples_to_duration(self.samples);
+        let origin = self.origin;
+        Ok(events
+            .into_iter()
+            .map(|event| clamp(event, end).offset(origin))
+            .collect())
+    }
+
+    /// Number of samples pushed so far, at the engine's sample rate,
+    /// including any still buffered. Padding isn't counted.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Timestamp of the end of the audio pushed so far.
+    pub fn position(&self) -> Duration {
+        self.origin + samples_to_duration(self.samples)
+    }
+
+    pub fn is_speech(&self) -> bool {
+        self.events.is_speech()
+    }
+
+    /// Starts over at the origin, dropping any buffered audio.
+    pub fn reset(&mut self) {
+        self.stream.reset();
+        self.events.reset();
+        self.samples = 0;
+    }
+
+    pub fn stream(&self) -> &CobraStream {
+        &self.stream
+    }
+
+    pub fn into_inner(self) -> CobraStream {
+        self.stream
+    }
+}
+
+/// Moves `event` back to `end` if it's later, as when it falls at the end of a
+/// padded final frame.
+fn clamp(event: Event, end: Duration) -> Event {
+    match event {
+        Event::SpeechStart { at } => Event::SpeechStart { at: at.min(end) },
+        Event::SpeechEnd { at, duration } if at > end => Event::SpeechEnd {
+            at: end,
+            duration: duration.saturating_sub(at - end),
+        },
+        event => event,
+    }
+}
+
+type CreateSession<K> = Box<dyn FnMut(&K) -> Result<VadSession> + Send>;
+
+struct ManagedSession {
+    session: VadSession,
+    events: Vec<Event>,
+    last_active: Instant,
+}
+
+impl ManagedSession {
+    /// Flushes the session, returning every event it has left.
+    fn finish(mut self) -> Result<Vec<Event>> {
+        let events = self.session.finish()?;
+        self.events.extend(events);
+        Ok(self.events)
+    }
+}
+
+/// Keeps an independent [`VadSession`] per stream, e.g. one per call on a
+/// server, creating each the first time audio arrives for its key. Events
+/// are queued per key until drained.
+///
+/// Sessions that stop receiving audio can be evicted automatically, see
+/// [`with_idle_timeout`](Self::with_idle_timeout) and
+/// [`with_max_sessions`](Self::with_max_sessions).
+pub struct SessionManager<K> {
+    create: CreateSession<K>,
+    sessions: HashMap<K, ManagedSession>,
+    idle_timeout: Option<Duration>,
+    max_sessions: Option<usize>,
+    evicted: Vec<(K, Result<Vec<Event>>)>,
+}
+
+impl<K: Hash + Eq> SessionManager<K> {
+    /// Uses `create` to set up the session for each new key.
+    pub fn new<F>(create: F) -> Self
+    where
+        F: FnMut(&K) -> Result<VadSession> + Send + 'static,
+    {
+        SessionManager {
+            create: Box::new(create),
+            sessions: HashMap::new(),
+            idle_timeout: None,
+            max_sessions: None,
+            evicted: Vec::new(),
+        }
+    }
+
+    /// Evicts sessions that haven't received audio for `timeout`. Eviction
+    /// happens on [`push`](Self::push), or explicitly with
+    /// [`evict_idle`](Self::evict_idle).
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Keeps at most `max` sessions. When audio arrives for a new key while
+    /// at the limit, the session that has been idle the longest is evicted
+    /// to make room.
+    pub fn with_max_sessions(mut self, max: usize) -> Self {
+        assert!(max > 0, "at least one session must be allowed");
+        self.max_sessions = Some(max);
+        self
+    }
+
+    /// Processes `pcm` for the stream identified by `key`, queueing the
+    /// resulting events.
+    pub fn push(&mut self, key: K, pcm: &[i16]) -> Result<()> {
+        let now = Instant::now();
+        self.evict_idle_at(now);
+        if let Some(max) = self.max_sessions
+            && self.sessions.len() >= max
+            && !self.sessions.contains_key(&key)
+        {
+            self.evict_oldest();
+        }
+        let managed = match self.sessions.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let session = (self.create)(entry.key())?;
+                entry.insert(ManagedSession {
+                    session,
+                    events: Vec::new(),
+                    last_active: now,
+                })
+            }
+        };
+        managed.last_active = now;
+        let events = managed.session.push(pcm)?;
+        managed.events.extend(events);
+        Ok(())
+    }
+
+    /// Flushes and drops every session that has been idle for longer than
+    /// the idle timeout, if one was configured.
+    pub fn evict_idle(&mut self) {
+        self.evict_idle_at(Instant::now());
+    }
+
+    fn evict_idle_at(&mut self, now: Instant) {
+        let Some(timeout) = self.idle_timeout else {
+            return;
+        };
+        let expired = self
+            .sessions
+            .extract_if(|_, managed| now.duration_since(managed.last_active) > timeout);
+        self.evicted
+            .extend(expired.map(|(key, managed)| (key, managed.finish())));
+    }
+
+    fn evict_oldest(&mut self) {
+        let Some(oldest) = self.sessions.values().map(|m| m.last_active).min() else {
+            return;
+        };
+        let evicted = self
+            .sessions
+            .extract_if(|_, managed| managed.last_active == oldest)
+            .next();
+        if let Some((key, managed)) = evicted {
+            self.evicted.push((key, managed.finish()));
+        }
+    }
+
+    /// Takes the sessions evicted so far, along with the result of flushing
+    /// each: its undrained events, including any ended speech.
+    pub fn drain_evicted(&mut self) -> Vec<(K, Result<Vec<Event>>)> {
+        mem::take(&mut self.evicted)
+    }
+
+    /// Takes the events queued for `key` so far.
+    pub fn drain(&mut self, key: &K) -> Vec<Event> {
+        self.sessions
+            .get_mut(key)
+            .map(|managed| mem::take(&mut managed.events))
+            .unwrap_or_default()
+    }
+
+    /// Ends the stream identified by `key`, returning its undrained events
+    /// along with those from flushing the session.
+    pub fn finish(&mut self, key: &K) -> Result<Vec<Event>> {
+        match self.sessions.remove(key) {
+            Some(managed) => managed.finish(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Drops the stream identified by `key` without flushing it.
+    pub fn remove(&mut self, key: &K) -> Option<VadSession> {
+        self.sessions.remove(key).map(|managed| managed.session)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&VadSession> {
+        self.sessions.get(key).map(|managed| &managed.session)
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.sessions.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.sessions.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_clamp() {
+        let end = Duration::from_millis(100);
+        assert_eq!(
+            clamp(
+                Event::SpeechEnd {
+                    at: Duration::from_millis(128),
+                    duration: Duration::from_millis(64),
+                },
+                end
+            ),
+            Event::SpeechEnd {
+                at: end,
+                duration: Duration::from_millis(36),
+            }
+        );
+        let event = Event::SpeechEnd {
+            at: Duration::from_millis(96),
+            duration: Duration::from_millis(64),
+        };
+        assert_eq!(clamp(event, end), event);
+        assert_eq!(
+            clamp(
+                Event::SpeechStart {
+                    at: Duration::from_millis(128)
+                },
+                end
+            ),
+            Event::SpeechStart { at: end }
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::{Cobra, Confidence, Result, lock};
+
+/// A handle to a [`Cobra`] that can be cloned and shared between threads.
+/// Calls are serialized by an internal lock, so threads take turns.
+///
+/// Cobra carries context from one frame to the next, so sharing an instance
+/// between unrelated streams mixes their context; prefer a separate instance
+/// per stream when accuracy matters.
+#[derive(Debug, Clone)]
+pub struct SharedCobra {
+    cobra: Arc<Mutex<Cobra>>,
+}
+
+impl SharedCobra {
+    pub fn new(cobra: Cobra) -> Self {
+        SharedCobra {
+            cobra: Arc::new(Mutex::new(cobra)),
+        }
+    }
+
+    pub fn sample_rate(&self) -> i32 {
+        crate::sample_rate()
+    }
+
+    pub fn frame_length(&self) -> i32 {
+        crate::frame_length()
+    }
+
+    /// Processes a frame of exactly `frame_length()` samples, waiting for
+    /// any other thread using the instance to finish first.
+    pub fn process(&self, pcm: &[i16]) -> Result<Confidence> {
+        self.lock().process(pcm)
+    }
+
+    /// Locks the instance for a series of calls that mustn't be interleaved
+    /// with other threads' frames.
+    pub fn lock(&self) -> MutexGuard<'_, Cobra> {
+        lock(&self.cobra)
+    }
+
+    /// Returns the instance if this is the last handle to it.
+    pub fn try_into_inner(self) -> std::result::Result<Cobra, Self> {
+        match Arc::try_unwrap(self.cobra) {
+            Ok(cobra) => Ok(cobra.into_inner().unwrap_or_else(PoisonError::into_inner)),
+            Err(cobra) => Err(SharedCobra { cobra }),
+        }
+    }
+}
+
+impl From<Cobra> for SharedCobra {
+    fn from(cobra: Cobra) -> Self {
+        SharedCobra::new(cobra)
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::{Confidence, frame_duration};
+
+/// Post-processes the confidence of each frame before it reaches downstream
+/// logic, e.g. to stop single noisy frames from triggering speech.
+pub trait Smoother: Send {
+    fn smooth(&mut self, confidence: Confidence) -> Confidence;
+
+    fn reset(&mut self);
+}
+
+/// Exponential moving average.
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: f32,
+    value: Option<f32>,
+}
+
+impl Ema {
+    /// `alpha` is the weight of each new frame, in `(0, 1]`.
+    pub fn new(alpha: f32) -> Self {
+        Ema {
+            alpha: alpha.clamp(f32::MIN_POSITIVE, 1.0),
+            value: None,
+        }
+    }
+
+    /// An average whose response to a step change reaches ~63% after
+    /// `time_constant` of audio.
+    pub fn with_time_constant(time_constant: Duration) -> Self {
+        let frames = time_constant.as_secs_f64() / frame_duration().as_secs_f64();
+        Self::new((1.0 - (-1.0 / frames).exp()) as f32)
+    }
+}
+
+impl Smoother for Ema {
+    fn smooth(&mut self, confidence: Confidence) -> Confidence {
+        let value = match self.value {
+            Some(value) => value + self.alpha * (confidence.get() - value),
+            None => confidence.get(),
+        };
+        self.value = Some(value);
+        Confidence::new(value)
+    }
+
+    fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+/// Simple moving average over a fixed number of frames.
+#[derive(Debug, Clone)]
+pub struct MovingAverage {
+    window: VecDeque<f32>,
+    len: usize,
+    sum: f32,
+}
+
+impl MovingAverage {
+    pub fn new(frames: usize) -> Self {
+        let len = frames.max(1);
+        MovingAverage {
+            window: VecDeque::with_capacity(len),
+            len,
+            sum: 0.0,
+        }
+    }
+
+    /// An average over the frames covering `window` of audio.
+    pub fn with_window(window: Duration) -> Self {
+        let frames = (window.as_secs_f64() / frame_duration().as_secs_f64()).round();
+        Self::new(frames as usize)
+    }
+}
+
+impl Smoother for MovingAverage {
+    fn smooth(&mut self, confidence: Confidence) -> Confidence {
+        if self.window.len() == self.len {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        self.window.push_back(confidence.get());
+        self.sum += confidence.get();
+        Confidence::new(self.sum / self.window.len() as f32)
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn smooth_all<S: Smoother>(smoother: &mut S, confidences: &[f32]) -> Vec<f32> {
+        confidences
+            .iter()
+            .map(|&c| smoother.smooth(Confidence::new(c)).get())
+            .collect()
+    }
+
+    #[test]
+    fn check_ema() {
+        let mut ema = Ema::new(0.5);
+        assert_eq!(
+            smooth_all(&mut ema, &[0.0, 1.0, 1.0, 0.0]),
+            [0.0, 0.5, 0.75, 0.375]
+        );
+        ema.reset();
+        assert_eq!(smooth_all(&mut ema, &[1.0]), [1.0]);
+    }
+
+    #[test]
+    fn check_ema_time_constant() {
+        let mut ema = Ema::with_time_constant(frame_duration() * 10);
+        let mut value = 0.0;
+        ema.smooth(Confidence::new(0.0));
+        for _ in 0..10 {
+            value = ema.smooth(Confidence::new(1.0)).get();
+        }
+        assert!((value - 0.632).abs() < 0.01);
+    }
+
+    #[test]
+    fn check_moving_average() {
+        let mut average = MovingAverage::new(2);
+        assert_eq!(
+            smooth_all(&mut average, &[1.0, 0.0, 0.5, 0.5]),
+            [1.0, 0.5, 0.25, 0.5]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use crate::{Confidence, duration_to_frames, frames_to_duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VadState {
+    Silence,
+    /// Confidence rose above the enter threshold, but not for long enough to
+    /// count as speech yet.
+    SpeechOnset,
+    Speech,
+    /// Confidence dropped below the exit threshold, but not for long enough
+    /// to count as silence yet.
+    SpeechOffset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    pub from: VadState,
+    pub to: VadState,
+    /// Start of the frame that caused the transition.
+    pub at: Duration,
+}
+
+/// An explicit voice activity state machine, for callers that need to know
+/// not just whether there is speech but how confident the detector is in
+/// that decision (e.g. to drive a UI).
+#[derive(Debug, Clone)]
+pub struct VadStateMachine {
+    enter_threshold: f32,
+    exit_threshold: f32,
+    onset_frames: u64,
+    offset_frames: u64,
+    state: VadState,
+    since: u64,
+    frames: u64,
+    count: u64,
+}
+
+impl VadStateMachine {
+    /// Panics if `exit_threshold` is greater than `enter_threshold`.
+    pub fn new(enter_threshold: f32, exit_threshold: f32) -> Self {
+        assert!(
+            exit_threshold <= enter_threshold,
+            "exit threshold ({}) must not exceed enter threshold ({})",
+            exit_threshold,
+            enter_threshold
+        );
+        VadStateMachine {
+            enter_threshold,
+            exit_threshold,
+            onset_frames: 0,
+            offset_frames: 0,
+            state: VadState::Silence,
+            since: 0,
+            frames: 0,
+            count: 0,
+        }
+    }
+
+    /// How long confidence must stay above the enter threshold before
+    /// `SpeechOnset` becomes `Speech`.
+    pub fn with_onset(mut self, onset: Duration) -> Self {
+        self.onset_frames = duration_to_frames(onset);
+        self
+    }
+
+    /// How long confidence must stay below the exit threshold before
+    /// `SpeechOffset` becomes `Silence`.
+    pub fn with_offset(mut self, offset: Duration) -> Self {
+        self.offset_frames = duration_to_frames(offset);
+        self
+    }
+
+    pub fn state(&self) -> VadState {
+        self.state
+    }
+
+    /// When the current state was entered.
+    pub fn state_since(&self) -> Duration {
+        frames_to_duration(self.since)
+    }
+
+    /// Feeds the confidence of the next frame, returning the transition it
+    /// caused, if any.
+    pub fn update(&mut self, confidence: Confidence) -> Option<Transition> {
+        let frame = self.frames;
+        self.frames += 1;
+        let next = match self.state {
+            VadState::Silence if confidence.is_speech(self.enter_threshold) => {
+                self.count = 1;
+                if self.count >= self.onset_frames {
+                    VadState::Speech
+                } else {
+                    VadState::SpeechOnset
+                }
+            }
+            VadState::SpeechOnset if confidence.is_speech(self.enter_threshold) => {
+                self.count += 1;
+                if self.count >= self.onset_frames {
+                    VadState::Speech
+                } else {
+                    VadState::SpeechOnset
+                }
+            }
+            VadState::SpeechOnset => VadState::Silence,
+            VadState::Speech if !confidence.is_speech(self.exit_threshold) => {
+                self.count = 1;
+                if self.count >= self.offset_frames {
+                    VadState::Silence
+                } else {
+                    VadState::SpeechOffset
+                }
+            }
+            VadState::SpeechOffset if !confidence.is_speech(self.exit_threshold) => {
+                self.count += 1;
+                if self.count >= self.offset_frames {
+                    VadState::Silence
+                } else {
+                    VadState::SpeechOffset
+                }
+            }
+            VadState::SpeechOffset => VadState::Speech,
+            state => state,
+        };
+        if next == self.state {
+            return None;
+        }
+        let transition = Transition {
+            from: self.state,
+            to: next,
+            at: frames_to_duration(frame),
+        };
+        self.state = next;
+        self.since = frame;
+        Some(transition)
+    }
+
+    pub fn reset(&mut self) {
+        self.state = VadState::Silence;
+        self.since = 0;
+        self.frames = 0;
+        self.count = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_state_transitions() {
+        let mut vad = VadStateMachine::new(0.6, 0.4)
+            .with_onset(frames_to_duration(2))
+            .with_offset(frames_to_duration(2));
+        let states: Vec<VadState> = [0.1, 0.7, 0.2, 0.7, 0.7, 0.5, 0.3, 0.5, 0.3, 0.3]
+            .iter()
+            .map(|&c| {
+                vad.update(Confidence::new(c));
+                vad.state()
+            })
+            .collect();
+        use VadState::*;
+        assert_eq!(
+            states,
+            [
+                Silence,
+                SpeechOnset,
+                Silence,
+                SpeechOnset,
+                Speech,
+                Speech,
+                SpeechOffset,
+                Speech,
+                SpeechOffset,
+                Silence
+            ]
+        );
+        assert_eq!(vad.state_since(), frames_to_duration(9));
+    }
+
+    #[test]
+    fn check_transitions_are_reported() {
+        let mut vad = VadStateMachine::new(0.5, 0.5);
+        assert_eq!(vad.update(Confidence::new(0.1)), None);
+        assert_eq!(
+            vad.update(Confidence::new(0.9)),
+            Some(Transition {
+                from: VadState::Silence,
+                to: VadState::Speech,
+                at: frames_to_duration(1),
+            })
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use crate::detector::HysteresisDetector;
+use crate::{Confidence, frames_to_duration};
+
+/// Aggregate figures for everything pushed into a [`VadStats`] so far.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StatsSnapshot {
+    pub audio_time: Duration,
+    pub speech_time: Duration,
+    /// Fraction of the audio classified as speech, between 0 and 1.
+    pub speech_ratio: f32,
+    /// Number of speech segments started, including one still in progress.
+    pub segments: u64,
+    pub mean_confidence: Confidence,
+}
+
+/// Accumulates speech statistics over a stream of confidences, e.g. for call
+/// analytics.
+#[derive(Debug, Clone)]
+pub struct VadStats {
+    detector: HysteresisDetector,
+    frames: u64,
+    speech_frames: u64,
+    segments: u64,
+    confidence_sum: f64,
+}
+
+impl VadStats {
+    pub fn new(threshold: f32) -> Self {
+        Self::with_detector(HysteresisDetector::new(threshold, threshold))
+    }
+
+    /// Uses `detector` to decide which frames are speech.
+    pub fn with_detector(detector: HysteresisDetector) -> Self {
+        VadStats {
+            detector,
+            frames: 0,
+            speech_frames: 0,
+            segments: 0,
+            confidence_sum: 0.0,
+        }
+    }
+
+    /// Feeds the confidence of the next frame.
+    pub fn push(&mut self, confidence: Confidence) {
+        let was_speech = self.detector.is_speech();
+        if self.detector.update(confidence) {
+            self.speech_frames += 1;
+            if !was_speech {
+                self.segments += 1;
+            }
+        }
+        self.frames += 1;
+        self.confidence_sum += confidence.get() as f64;
+    }
+
+    pub fn snapshot(&self) -> StatsSnapshot {
+        if self.frames == 0 {
+            return StatsSnapshot::default();
+        }
+        StatsSnapshot {
+            audio_time: frames_to_duration(self.frames),
+            speech_time: frames_to_duration(self.speech_frames),
+            speech_ratio: self.speech_frames as f32 / self.frames as f32,
+            segments: self.segments,
+            mean_confidence: Confidence::new((self.confidence_sum / self.frames as f64) as f32),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.detector.reset();
+        self.frames = 0;
+        self.speech_frames = 0;
+        self.segments = 0;
+        self.confidence_sum = 0.0;
+    }
+}
+
+/// How long calls into the engine took, see [`Cobra::latency_stats`].
+///
+/// [`Cobra::latency_stats`]: crate::Cobra::latency_stats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LatencyStats {
+    /// Number of frames processed.
+    pub frames: u64,
+    pub min: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+    /// 99th percentile over the most recent frames.
+    pub p99: Duration,
+}
+
+/// Number of recent latencies kept for the percentile.
+const LATENCY_WINDOW: usize = 1024;
+
+/// Records per-frame processing latencies.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LatencyRecorder {
+    frames: u64,
+    total: Duration,
+    min: Duration,
+    max: Duration,
+    recent: Vec<Duration>,
+}
+
+impl LatencyRecorder {
+    pub(crate) fn record(&mut self, latency: Duration) {
+        if self.frames == 0 || latency < self.min {
+            self.min = latency;
+        }
+        self.max = self.max.max(latency);
+        self.total += latency;
+        if self.recent.len() < LATENCY_WINDOW {
+            self.recent.push(latency);
+        } else {
+            self.recent[(self.frames % LATENCY_WINDOW as u64) as usize] = latency;
+        }
+        self.frames += 1;
+    }
+
+    pub(crate) fn stats(&self) -> LatencyStats {
+        if self.frames == 0 {
+            return LatencyStats::default();
+        }
+        let mut recent = self.recent.clone();
+        recent.sort_unstable();
+        // Nearest-rank percentile.
+        let rank = (recent.len() * 99).div_ceil(100);
+        LatencyStats {
+            frames: self.frames,
+            min: self.min,
+            mean: Duration::from_nanos((self.total.as_nanos() / self.frames as u128) as u64),
+            max: self.max,
+            p99: recent[rank - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_snapshot() {
+        let mut stats = VadStats::new(0.5);
+        assert_eq!(stats.snapshot(), StatsSnapshot::default());
+        for c in [0.1, 0.9, 0.7, 0.1, 0.6, 0.2, 0.1, 0.3] {
+            stats.push(Confidence::new(c));
+        }
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.audio_time, frames_to_duration(8));
+        assert_eq!(snapshot.speech_time, frames_to_duration(3));
+        assert_eq!(snapshot.speech_ratio, 0.375);
+        assert_eq!(snapshot.segments, 2);
+        assert!((snapshot.mean_confidence.get() - 0.375).abs() < 1e-6);
+    }
+
+    #[test]
+    fn check_latency_stats() {
+        let mut recorder = LatencyRecorder::default();
+        assert_eq!(recorder.stats(), LatencyStats::default());
+        for micros in (1..=200).rev() {
+            recorder.record(Duration::from_micros(micros));
+        }
+        let stats = recorder.stats();
+        assert_eq!(stats.frames, 200);
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(stats.max, Duration::from_micros(200));
+        assert_eq!(stats.mean, Duration::from_nanos(100_500));
+        assert_eq!(stats.p99, Duration::from_micros(198));
+    }
+}
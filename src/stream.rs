@@ -0,0 +1,363 @@
+use std::mem;
+use std::time::Duration;
+
+use crate::calibration::Calibration;
+use crate::g711::Law;
+use crate::preprocess::Preprocessor;
+use crate::resample::Resampler;
+#[cfg(feature = "resample")]
+use crate::resample::RubatoResampler;
+use crate::smoothing::Smoother;
+use crate::telephony::TELEPHONY_SAMPLE_RATE;
+use crate::{
+    Cobra, Confidence, ConfidenceHistory, Error, Instant, Padding, Result, duration_to_frames,
+    frames_to_duration,
+};
+
+/// Fails unless `resampler` takes G.711's sample rate.
+fn check_g711_rate(resampler: Option<&dyn Resampler>) -> Result<()> {
+    let reason = match resampler.map(|resampler| resampler.input_rate()) {
+        Some(TELEPHONY_SAMPLE_RATE) => return Ok(()),
+        Some(rate) => format!(
+            "G.711 is sampled at {} Hz, but the resampler takes {} Hz",
+            TELEPHONY_SAMPLE_RATE, rate
+        ),
+        None => format!(
+            "G.711 is sampled at {} Hz and no resampler is configured",
+            TELEPHONY_SAMPLE_RATE
+        ),
+    };
+    Err(Error::UnsupportedFormat { reason })
+}
+
+/// Processing time divided by the duration of the audio processed.
+fn rtf(busy: Duration, frames: u64) -> f64 {
+    busy.as_secs_f64() / frames_to_duration(frames).as_secs_f64()
+}
+
+/// Periodically reports the real-time factor of the latest stretch of audio.
+struct RtfReport {
+    every: u64,
+    busy: Duration,
+    frames: u64,
+    callback: Box<dyn FnMut(f64) + Send>,
+}
+
+/// Accepts audio in chunks of any length, runs each complete frame through
+/// Cobra and applies the configured post-processing to the confidences.
+pub struct CobraStream {
+    cobra: Cobra,
+    frame_length: usize,
+    buf: Vec<i16>,
+    preprocessors: Vec<Box<dyn Preprocessor>>,
+    scratch: Vec<i16>,
+    calibration: Option<Calibration>,
+    smoother: Option<Box<dyn Smoother>>,
+    history: Option<ConfidenceHistory>,
+    resampler: Option<(Box<dyn Resampler>, Vec<i16>)>,
+    busy: Duration,
+    processed: u64,
+    // Samples at the engine's rate, not counting padding.
+    fed: u64,
+    rtf_report: Option<RtfReport>,
+}
+
+impl CobraStream {
+    pub fn new(cobra: Cobra) -> Self {
+        let frame_length = cobra.frame_length() as usize;
+        CobraStream {
+            cobra,
+            frame_length,
+            buf: Vec::with_capacity(frame_length),
+            preprocessors: Vec::new(),
+            scratch: Vec::new(),
+            calibration: None,
+            smoother: None,
+            history: None,
+            resampler: None,
+            busy: Duration::ZERO,
+            processed: 0,
+            fed: 0,
+            rtf_report: None,
+        }
+    }
+
+    /// Runs each frame through `preprocessor` before Cobra sees it. Multiple
+    /// preprocessors are applied in the order they were added.
+    pub fn with_preprocessor<P: Preprocessor + 'static>(mut self, preprocessor: P) -> Self {
+        self.preprocessors.push(Box::new(preprocessor));
+        self
+    }
+
+    /// Maps raw confidences through `calibration`, ahead of any smoothing.
+    pub fn with_calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    /// Smooths confidences before they are returned from [`push`](Self::push).
+    pub fn with_smoother<S: Smoother + 'static>(mut self, smoother: S) -> Self {
+        self.smoother = Some(Box::new(smoother));
+        self
+    }
+
+    /// Retains the confidences returned over the last `duration` of audio,
+    /// available through [`history`](Self::history).
+    pub fn with_history(mut self, duration: Duration) -> Self {
+        self.history = Some(ConfidenceHistory::with_duration(duration));
+        self
+    }
+
+    /// Passes audio through `resampler` before processing, so that it can
+    /// be pushed at a rate other than Cobra's.
+    pub fn with_resampler<R: Resampler + 'static>(mut self, resampler: R) -> Self {
+        self.resampler = Some((Box::new(resampler), Vec::new()));
+        self
+    }
+
+    /// Accepts audio sampled at `sample_rate` instead of Cobra's, resampling
+    /// it internally with a [`RubatoResampler`].
+    #[cfg(feature = "resample")]
+    pub fn with_input_rate(self, sample_rate: u32) -> Result<Self> {
+        let native = self.cobra.sample_rate() as u32;
+        if sample_rate == native {
+            return Ok(self);
+        }
+        Ok(self.with_resampler(RubatoResampler::new(sample_rate, native)?))
+    }
+
+    /// Calls `callback` with the real-time factor, as per
+    /// [`rtf`](Self::rtf), of every `interval` of audio processed, e.g. to
+    /// log whether a live stream is being kept up with.
+    pub fn with_rtf_reporting<F>(mut self, interval: Duration, callback: F) -> Self
+    where
+        F: FnMut(f64) + Send + 'static,
+    {
+        self.rtf_report = Some(RtfReport {
+            every: duration_to_frames(interval).max(1),
+            busy: Duration::ZERO,
+            frames: 0,
+            callback: Box::new(callback),
+        });
+        self
+    }
+
+    /// Buffers `pcm` and returns the confidences of any frames it completed.
+    pub fn push(&mut self, pcm: &[i16]) -> Result<Vec<Confidence>> {
+        let start = Instant::now();
+        let confidences = self.push_resampled(pcm)?;
+        self.record(start.elapsed(), confidences.len() as u64);
+        Ok(confidences)
+    }
+
+    fn push_resampled(&mut self, pcm: &[i16]) -> Result<Vec<Confidence>> {
+        if let Some((resampler, resampled)) = &mut self.resampler {
+            let mut resampled = mem::take(resampled);
+            resampled.clear();
+            resampler.process(pcm, &mut resampled);
+            let confidences = self.push_native(&resampled);
+            if let Some((_, buf)) = &mut self.resampler {
+                *buf = resampled;
+            }
+            return confidences;
+        }
+        self.push_native(pcm)
+    }
+
+    /// Decodes G.711 `bytes`, e.g. a raw RTP payload, and pushes the audio.
+    /// G.711 is sampled at 8 kHz, so this needs a resampler from 8 kHz
+    /// configured with [`with_resampler`](Self::with_resampler), and fails
+    /// with [`Error::UnsupportedFormat`] otherwise.
+    pub fn push_g711(&mut self, bytes: &[u8], law: Law) -> Result<Vec<Confidence>> {
+        check_g711_rate(self.resampler.as_ref().map(|(resampler, _)| &**resampler))?;
+        self.push(&law.decode(bytes))
+    }
+
+    fn push_native(&mut self, mut pcm: &[i16]) -> Result<Vec<Confidence>> {
+        self.fed += pcm.len() as u64;
+        let mut confidences = Vec::with_capacity((self.buf.len() + pcm.len()) / self.frame_length);
+        if !self.buf.is_empty() {
+            let take = (self.frame_length - self.buf.len()).min(pcm.len());
+            self.buf.extend_from_slice(&pcm[..take]);
+            pcm = &pcm[take..];
+            if self.buf.len() < self.frame_length {
+                return Ok(confidences);
+            }
+            let confidence = analyze(
+                &mut self.cobra,
+                &mut self.preprocessors,
+                &mut self.scratch,
+                &self.buf,
+            )?;
+            self.buf.clear();
+            confidences.push(self.postprocess(confidence));
+        }
+        let mut frames = pcm.chunks_exact(self.frame_length);
+        for frame in &mut frames {
+            let confidence = analyze(
+                &mut self.cobra,
+                &mut self.preprocessors,
+                &mut self.scratch,
+                frame,
+            )?;
+            confidences.push(self.postprocess(confidence));
+        }
+        self.buf.extend_from_slice(frames.remainder());
+        Ok(confidences)
+    }
+
+    fn postprocess(&mut self, mut confidence: Confidence) -> Confidence {
+        if let Some(calibration) = &self.calibration {
+            confidence = calibration.apply(confidence);
+        }
+        let confidence = match &mut self.smoother {
+            Some(smoother) => smoother.smooth(confidence),
+            None => confidence,
+        };
+        if let Some(history) = &mut self.history {
+            history.push(confidence);
+        }
+        confidence
+    }
+
+    /// The most recent confidences, oldest first. Empty unless a history was
+    /// configured with [`with_history`](Self::with_history).
+    pub fn history(&self) -> &[Confidence] {
+        self.history
+            .as_ref()
+            .map_or(&[], ConfidenceHistory::as_slice)
+    }
+
+    /// Processes any buffered samples, the last of them as a final frame
+    /// padded as per `padding`, and returns their confidences.
+    pub fn finish(&mut self, padding: Padding) -> Result<Vec<Confidence>> {
+        let start = Instant::now();
+        let confidences = self.finish_untimed(padding)?;
+        self.record(start.elapsed(), confidences.len() as u64);
+        Ok(confidences)
+    }
+
+    fn finish_untimed(&mut self, padding: Padding) -> Result<Vec<Confidence>> {
+        let mut confidences = match &mut self.resampler {
+            Some((resampler, _)) => {
+                let mut tail = Vec::new();
+                resampler.flush(&mut tail);
+                self.push_native(&tail)?
+            }
+            None => Vec::new(),
+        };
+        if !self.buf.is_empty() {
+            for preprocessor in &mut self.preprocessors {
+                preprocessor.process(&mut self.buf);
+            }
+            let (confidence, _) = self.cobra.process_partial(&self.buf, padding)?;
+            self.buf.clear();
+            confidences.push(self.postprocess(confidence));
+        }
+        Ok(confidences)
+    }
+
+    fn record(&mut self, busy: Duration, frames: u64) {
+        self.busy += busy;
+        self.processed += frames;
+        if let Some(report) = &mut self.rtf_report {
+            report.busy += busy;
+            report.frames += frames;
+            if report.frames >= report.every {
+                (report.callback)(rtf(report.busy, report.frames));
+                report.busy = Duration::ZERO;
+                report.frames = 0;
+            }
+        }
+    }
+
+    /// Time spent processing divided by the duration of the audio
+    /// processed, over the life of the stream, or `None` before the first
+    /// frame. Below 1.0 the stream keeps up with live audio; at 0.05, a
+    /// second of audio takes 50 ms to process.
+    pub fn rtf(&self) -> Option<f64> {
+        (self.processed > 0).then(|| rtf(self.busy, self.processed))
+    }
+
+    /// Number of samples taken in at the engine's sample rate over the life of
+    /// the stream, including any buffered but not the padding of final frames.
+    pub(crate) fn fed(&self) -> u64 {
+        self.fed
+    }
+
+    /// Sample rate of the audio pushed, which is the resampler's if there is
+    /// one.
+    pub(crate) fn input_rate(&self) -> u32 {
+        match &self.resampler {
+            Some((resampler, _)) => resampler.input_rate(),
+            None => self.cobra.sample_rate() as u32,
+        }
+    }
+
+    /// Number of buffered samples waiting for the next frame to complete.
+    pub fn pending(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Drops any buffered audio and resets the post-processing state.
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        if let Some((resampler, _)) = &mut self.resampler {
+            resampler.reset();
+        }
+        for preprocessor in &mut self.preprocessors {
+            preprocessor.reset();
+        }
+        if let Some(smoother) = &mut self.smoother {
+            smoother.reset();
+        }
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+    }
+
+    pub fn cobra(&self) -> &Cobra {
+        &self.cobra
+    }
+
+    pub fn into_inner(self) -> Cobra {
+        self.cobra
+    }
+}
+
+/// Runs `frame` through the preprocessors, if any, and then Cobra.
+fn analyze(
+    cobra: &mut Cobra,
+    preprocessors: &mut [Box<dyn Preprocessor>],
+    scratch: &mut Vec<i16>,
+    frame: &[i16],
+) -> Result<Confidence> {
+    if preprocessors.is_empty() {
+        return cobra.process(frame);
+    }
+    scratch.clear();
+    scratch.extend_from_slice(frame);
+    for preprocessor in preprocessors {
+        preprocessor.process(scratch);
+    }
+    cobra.process(scratch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resample::LinearResampler;
+
+    #[test]
+    fn check_g711_needs_8khz_resampler() {
+        assert!(check_g711_rate(Some(&LinearResampler::new(8000, 16000))).is_ok());
+        assert!(matches!(
+            check_g711_rate(Some(&LinearResampler::new(48000, 16000))),
+            Err(Error::UnsupportedFormat { .. })
+        ));
+        assert!(matches!(
+            check_g711_rate(None),
+            Err(Error::UnsupportedFormat { .. })
+        ));
+    }
+}
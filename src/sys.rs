@@ -0,0 +1 @@
+pub use pv_cobra_redux_sys::*;
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use crate::g711::Law;
+use crate::resample::LinearResampler;
+use crate::{Cobra, CobraStream, Confidence, Padding, Result, frames_to_duration};
+
+/// Sample rate of narrowband telephony audio.
+pub const TELEPHONY_SAMPLE_RATE: u32 = 8000;
+
+/// A processed frame, timed in the clock of the narrowband input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TelephonyFrame {
+    /// Index of the input sample at which the frame starts.
+    pub offset: u64,
+    pub at: Duration,
+    pub confidence: Confidence,
+}
+
+/// Accepts 8 kHz narrowband audio, e.g. from a phone call, and upsamples it
+/// to Cobra's sample rate internally.
+pub struct TelephonyStream {
+    stream: CobraStream,
+    input_rate: u64,
+    output_rate: u64,
+    frame_length: u64,
+    frames: u64,
+}
+
+impl TelephonyStream {
+    pub fn new(cobra: Cobra) -> Self {
+        Self::with_stream(CobraStream::new(cobra))
+    }
+
+    /// Uses `stream`, e.g. with a smoother configured, replacing any
+    /// resampler it has.
+    pub fn with_stream(stream: CobraStream) -> Self {
+        let output_rate = stream.cobra().sample_rate() as u32;
+        let frame_length = stream.cobra().frame_length() as u64;
+        TelephonyStream {
+            stream: stream.with_resampler(LinearResampler::new(TELEPHONY_SAMPLE_RATE, output_rate)),
+            input_rate: TELEPHONY_SAMPLE_RATE as u64,
+            output_rate: output_rate as u64,
+            frame_length,
+            frames: 0,
+        }
+    }
+
+    /// Pushes 8 kHz linear PCM, returning any frames it completed.
+    pub fn push(&mut self, pcm: &[i16]) -> Result<Vec<TelephonyFrame>> {
+        let confidences = self.stream.push(pcm)?;
+        Ok(self.timestamp(confidences))
+    }
+
+    /// Pushes G.711-encoded 8 kHz audio, e.g. a raw RTP payload.
+    pub fn push_g711(&mut self, bytes: &[u8], law: Law) -> Result<Vec<TelephonyFrame>> {
+        self.push(&law.decode(bytes))
+    }
+
+    /// Processes any buffered audio as a zero-padded final frame.
+    pub fn finish(&mut self) -> Result<Vec<TelephonyFrame>> {
+        let confidences = self.stream.finish(Padding::Zero)?;
+        Ok(self.timestamp(confidences))
+    }
+
+    fn timestamp(&mut self, confidences: Vec<Confidence>) -> Vec<TelephonyFrame> {
+        confidences
+            .into_iter()
+            .map(|confidence| {
+                let frame = self.frames;
+                self.frames += 1;
+                TelephonyFrame {
+                    offset: frame * self.frame_length * self.input_rate / self.output_rate,
+                    at: frames_to_duration(frame),
+                    confidence,
+                }
+            })
+            .collect()
+    }
+
+    /// Drops any buffered audio and restarts the clock.
+    pub fn reset(&mut self) {
+        self.stream.reset();
+        self.frames = 0;
+    }
+
+    pub fn into_inner(self) -> CobraStream {
+        self.stream
+    }
+}
@@ -0,0 +1,155 @@
+use std::io;
+
+use ::tokio::io::{AsyncRead, AsyncReadExt};
+use ::tokio::sync::{mpsc, oneshot};
+use ::tokio::task;
+
+use crate::reader::FrameBuffer;
+use crate::{
+    Cobra, Confidence, Endianness, Error, Padding, Result, SampleWidth, frame_length, pad_frame,
+};
+
+type Request = (Vec<i16>, oneshot::Sender<Result<Confidence>>);
+
+/// A handle to a [`Cobra`] running on Tokio's blocking thread pool, so that
+/// async handlers can process audio without blocking the runtime. Cloning
+/// the handle shares the same instance, and frames are processed in the
+/// order they were submitted.
+#[derive(Debug, Clone)]
+pub struct AsyncCobra {
+    requests: mpsc::Sender<Request>,
+    frame_length: usize,
+}
+
+impl AsyncCobra {
+    /// Moves `cobra` onto a blocking worker. Must be called from within a
+    /// Tokio runtime. The worker exits once every handle has been dropped.
+    pub fn new(mut cobra: Cobra) -> Self {
+        let frame_length = cobra.frame_length() as usize;
+        let (requests, mut rx) = mpsc::channel::<Request>(32);
+        task::spawn_blocking(move || {
+            while let Some((frame, reply)) = rx.blocking_recv() {
+                // The caller may have given up waiting, which is fine.
+                let _ = reply.send(cobra.process(&frame));
+            }
+        });
+        AsyncCobra {
+            requests,
+            frame_length,
+        }
+    }
+
+    pub fn frame_length(&self) -> usize {
+        self.frame_length
+    }
+
+    /// Processes a frame of exactly `frame_length()` samples on the worker.
+    pub async fn process(&self, frame: &[i16]) -> Result<Confidence> {
+        let (reply, response) = oneshot::channel();
+        self.requests
+            .send((frame.to_vec(), reply))
+            .await
+            .map_err(|_| Error::WorkerStopped)?;
+        response.await.map_err(|_| Error::WorkerStopped)?
+    }
+}
+
+/// Reads PCM from an async reader, e.g. a TCP socket or stdin, one frame at
+/// a time. The async counterpart of [`FrameReader`](crate::FrameReader).
+pub struct AsyncFrameReader<R> {
+    reader: R,
+    buf: FrameBuffer,
+}
+
+impl<R: AsyncRead + Unpin> AsyncFrameReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_frame_length(reader, frame_length() as usize)
+    }
+
+    /// Reads frames of `frame_length` samples instead of Cobra's.
+    pub fn with_frame_length(reader: R, frame_length: usize) -> Self {
+        AsyncFrameReader {
+            reader,
+            buf: FrameBuffer::new(frame_length),
+        }
+    }
+
+    /// Reads samples of the given width, which are scaled to 16 bits.
+    /// Defaults to [`SampleWidth::I16`].
+    pub fn with_sample_width(mut self, width: SampleWidth) -> Self {
+        self.buf.set_sample_width(width);
+        self
+    }
+
+    /// Reads samples in the given byte order. Defaults to
+    /// [`Endianness::Little`].
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.buf.set_endianness(endianness);
+        self
+    }
+
+    /// Reads the next frame, waiting on short reads until it is complete.
+    /// At the end of the input, a final partial frame is returned as is,
+    /// followed by `None`.
+    pub async fn read_frame(&mut self) -> Result<Option<&[i16]>> {
+        while !self.buf.is_full() {
+            match self.reader.read(self.buf.unfilled()).await {
+                Ok(0) => break,
+                Ok(n) => self.buf.advance(n),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(self.buf.take_frame())
+    }
+
+    /// Reads the next frame and runs it through `cobra`, zero-padding a
+    /// final partial frame. Returns `None` at the end of the input.
+    pub async fn read_confidence(&mut self, cobra: &AsyncCobra) -> Result<Option<Confidence>> {
+        let frame_length = cobra.frame_length();
+        let Some(frame) = self.read_frame().await? else {
+            return Ok(None);
+        };
+        let confidence = if frame.len() < frame_length {
+            let frame = pad_frame(frame, frame_length, Padding::Zero);
+            cobra.process(&frame).await?
+        } else {
+            cobra.process(frame).await?
+        };
+        Ok(Some(confidence))
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ::tokio::runtime::Builder;
+
+    use super::*;
+
+    #[test]
+    fn check_async_frames_across_short_reads() {
+        let samples: Vec<i16> = (0..7).map(|i| i * 1000 - 3000).collect();
+        let mut data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        data.push(0xff);
+        // Split mid-sample so that the first frame needs two reads.
+        let (head, tail) = data.split_at(3);
+        let mut reader = AsyncFrameReader::with_frame_length(head.chain(tail), 3);
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let frames = runtime.block_on(async {
+            let mut frames = Vec::new();
+            while let Some(frame) = reader.read_frame().await.unwrap() {
+                frames.push(frame.to_vec());
+            }
+            frames
+        });
+        assert_eq!(frames, [&samples[0..3], &samples[3..6], &samples[6..]]);
+    }
+}
@@ -0,0 +1,337 @@
+use std::cell::OnceCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::ptr;
+use std::slice;
+
+use js_sys::{Float32Array, Function, Int16Array, Object, Promise, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+
+pub(crate) use pv_cobra_redux_sys::{
+    pv_cobra, pv_status_t, pv_status_t_PV_STATUS_ACTIVATION_ERROR,
+    pv_status_t_PV_STATUS_ACTIVATION_LIMIT_REACHED, pv_status_t_PV_STATUS_ACTIVATION_REFUSED,
+    pv_status_t_PV_STATUS_ACTIVATION_THROTTLED, pv_status_t_PV_STATUS_INVALID_ARGUMENT,
+    pv_status_t_PV_STATUS_INVALID_STATE, pv_status_t_PV_STATUS_IO_ERROR,
+    pv_status_t_PV_STATUS_KEY_ERROR, pv_status_t_PV_STATUS_OUT_OF_MEMORY,
+    pv_status_t_PV_STATUS_RUNTIME_ERROR, pv_status_t_PV_STATUS_STOP_ITERATION,
+};
+
+use crate::{Error, Result};
+
+/// Reported as the path in [`Error::LibraryLoad`], as the module isn't loaded
+/// from one.
+const MODULE_NAME: &str = "pv_cobra.wasm";
+
+/// Cobra's Emscripten module, with its exports resolved. Pointers into the
+/// module's memory are offsets, so arguments and results are copied across.
+struct Module {
+    module: JsValue,
+    malloc: Function,
+    free: Function,
+    get_error_stack: Function,
+    free_error_stack: Function,
+    init: Function,
+    delete: Function,
+    process: Function,
+    // Constant for the life of the module, so read once.
+    sample_rate: i32,
+    frame_length: i32,
+    version: &'static CStr,
+    // Scratch space in the module's memory for `pv_cobra_process`.
+    pcm: u32,
+    is_voiced: u32,
+}
+
+thread_local! {
+    static MODULE: OnceCell<Module> = const { OnceCell::new() };
+}
+
+/// Uses `module`, an instance of Cobra's WebAssembly build, for the `wasm`
+/// feature. This has to happen before anything calls into the engine, and on
+/// the thread that uses it, as the module belongs to that thread's JavaScript
+/// realm. Once set, the module stays in use, and setting a different one
+/// fails.
+///
+/// The module must be an Emscripten `MODULARIZE` build of the engine whose
+/// exports return synchronously, i.e. one built without Asyncify, exporting
+/// `HEAPU8`, `_malloc`, `_free` and the `_pv_*` functions. The module in
+/// Picovoice's `@picovoice/cobra-web` package is an Asyncify build whose
+/// exports return promises, so it isn't supported and is rejected with
+/// [`Error::LibraryLoad`].
+pub fn load_module(module: JsValue) -> Result<()> {
+    MODULE.with(|cell| {
+        if let Some(loaded) = cell.get() {
+            if Object::is(&loaded.module, &module) {
+                return Ok(());
+            }
+            return Err(load_error("a different module is already loaded"));
+        }
+        let loaded = Module::load(module).map_err(load_error)?;
+        let _ = cell.set(loaded);
+        Ok(())
+    })
+}
+
+fn load_error<S: Into<String>>(reason: S) -> Error {
+    Error::LibraryLoad {
+        path: PathBuf::from(MODULE_NAME),
+        reason: reason.into(),
+    }
+}
+
+/// Fails unless [`load_module`] has been called.
+pub(crate) fn ensure_loaded() -> Result<()> {
+    if MODULE.with(|cell| cell.get().is_some()) {
+        Ok(())
+    } else {
+        Err(load_error(
+            "no module loaded on this thread; call `load_module` with it first",
+        ))
+    }
+}
+
+fn with_module<T>(f: impl FnOnce(&Module) -> T) -> T {
+    MODULE.with(|cell| {
+        let module = cell
+            .get()
+            .expect("no Cobra module loaded on this thread; call `load_module` with it first");
+        f(module)
+    })
+}
+
+impl Module {
+    fn load(module: JsValue) -> std::result::Result<Self, String> {
+        let export = |name: &str| {
+            Reflect::get(&module, &JsValue::from_str(name))
+                .ok()
+                .and_then(|export| export.dyn_into::<Function>().ok())
+                .ok_or_else(|| format!("`{}` isn't exported", name))
+        };
+        let number = |function: &Function| {
+            let name = String::from(function.name());
+            match function.call0(&JsValue::NULL) {
+                Ok(result) if result.is_instance_of::<Promise>() => Err(format!(
+                    "`{}` returns a promise, so the module is an Asyncify build, which \
+                     isn't supported",
+                    name
+                )),
+                Ok(result) => result.as_f64().ok_or_else(|| format!("`{}` failed", name)),
+                Err(_) => Err(format!("`{}` failed", name)),
+            }
+        };
+        let malloc = export("_malloc")?;
+        let free = export("_free")?;
+        let get_error_stack = export("_pv_get_error_stack")?;
+        let free_error_stack = export("_pv_free_error_stack")?;
+        let init = export("_pv_cobra_init")?;
+        let delete = export("_pv_cobra_delete")?;
+        let process = export("_pv_cobra_process")?;
+        let sample_rate = number(&export("_pv_sample_rate")?)? as i32;
+        let frame_length = number(&export("_pv_cobra_frame_length")?)? as i32;
+        let version = number(&export("_pv_cobra_version")?)? as u32;
+        let mut loaded = Module {
+            module,
+            malloc,
+            free,
+            get_error_stack,
+            free_error_stack,
+            init,
+            delete,
+            process,
+            sample_rate,
+            frame_length,
+            version: c"",
+            pcm: 0,
+            is_voiced: 0,
+        };
+        if !loaded.heap().is_instance_of::<Uint8Array>() {
+            return Err("`HEAPU8` isn't exported".to_string());
+        }
+        // Leaked, as callers expect the version to live for the life of the
+        // program, which the module does anyway.
+        let version = loaded.read_cstr(version);
+        loaded.version = Box::leak(version.into_boxed_c_str());
+        loaded.pcm = loaded
+            .malloc(loaded.frame_length as u32 * 2)
+            .ok_or("out of memory")?;
+        loaded.is_voiced = loaded.malloc(4).ok_or("out of memory")?;
+        Ok(loaded)
+    }
+
+    /// The module's memory, looked up on every use as growing it replaces
+    /// the view.
+    fn heap(&self) -> Uint8Array {
+        Reflect::get(&self.module, &JsValue::from_str("HEAPU8"))
+            .unwrap_or_default()
+            .unchecked_into()
+    }
+
+    fn malloc(&self, size: u32) -> Option<u32> {
+        call(&self.malloc, &[size.into()])
+            .filter(|ptr| *ptr != 0.0)
+            .map(|ptr| ptr as u32)
+    }
+
+    fn free(&self, ptr: u32) {
+        let _ = self.free.call1(&JsValue::NULL, &ptr.into());
+    }
+
+    fn read_u32(&self, ptr: u32) -> u32 {
+        let mut bytes = [0; 4];
+        self.heap().subarray(ptr, ptr + 4).copy_to(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn read_cstr(&self, ptr: u32) -> CString {
+        let heap = self.heap();
+        let mut end = ptr;
+        while end < heap.length() && heap.get_index(end) != 0 {
+            end += 1;
+        }
+        CString::new(heap.subarray(ptr, end).to_vec()).unwrap()
+    }
+}
+
+/// Calls `function`, returning its result if it's a number.
+fn call(function: &Function, args: &[JsValue]) -> Option<f64> {
+    let result = match args {
+        [] => function.call0(&JsValue::NULL),
+        [a] => function.call1(&JsValue::NULL, a),
+        [a, b] => function.call2(&JsValue::NULL, a, b),
+        [a, b, c] => function.call3(&JsValue::NULL, a, b, c),
+        _ => unreachable!(),
+    };
+    result.ok()?.as_f64()
+}
+
+fn status(result: Option<f64>) -> pv_status_t {
+    result.map_or(pv_status_t_PV_STATUS_RUNTIME_ERROR, |status| {
+        status as pv_status_t
+    })
+}
+
+fn handle(object: *mut pv_cobra) -> JsValue {
+    (object as usize as u32).into()
+}
+
+pub(crate) unsafe fn pv_sample_rate() -> i32 {
+    with_module(|module| module.sample_rate)
+}
+
+/// Copies the messages out of the module's memory into a null-terminated
+/// array, which is how [`pv_free_error_stack`] knows its length.
+pub(crate) unsafe fn pv_get_error_stack(
+    message_stack: *mut *mut *mut c_char,
+    message_stack_depth: *mut i32,
+) -> pv_status_t {
+    with_module(|module| {
+        let Some(out) = module.malloc(8) else {
+            return pv_status_t_PV_STATUS_OUT_OF_MEMORY;
+        };
+        let result = status(call(
+            &module.get_error_stack,
+            &[out.into(), (out + 4).into()],
+        ));
+        let stack = module.read_u32(out);
+        let depth = module.read_u32(out + 4);
+        module.free(out);
+        if result != 0 || stack == 0 {
+            return result;
+        }
+        let messages: Box<[*mut c_char]> = (0..depth)
+            .map(|i| module.read_cstr(module.read_u32(stack + i * 4)).into_raw())
+            .chain([ptr::null_mut()])
+            .collect();
+        let _ = module.free_error_stack.call1(&JsValue::NULL, &stack.into());
+        unsafe {
+            *message_stack = Box::into_raw(messages).cast();
+            *message_stack_depth = depth as i32;
+        }
+        result
+    })
+}
+
+pub(crate) unsafe fn pv_free_error_stack(message_stack: *mut *mut c_char) {
+    unsafe {
+        let mut depth = 0;
+        while !(*message_stack.add(depth)).is_null() {
+            drop(CString::from_raw(*message_stack.add(depth)));
+            depth += 1;
+        }
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            message_stack,
+            depth + 1,
+        )));
+    }
+}
+
+pub(crate) unsafe fn pv_cobra_init(
+    access_key: *const c_char,
+    object: *mut *mut pv_cobra,
+) -> pv_status_t {
+    let access_key = unsafe { CStr::from_ptr(access_key) }.to_bytes_with_nul();
+    with_module(|module| {
+        let Some(key) = module.malloc(access_key.len() as u32) else {
+            return pv_status_t_PV_STATUS_OUT_OF_MEMORY;
+        };
+        let Some(out) = module.malloc(4) else {
+            module.free(key);
+            return pv_status_t_PV_STATUS_OUT_OF_MEMORY;
+        };
+        module
+            .heap()
+            .subarray(key, key + access_key.len() as u32)
+            .copy_from(access_key);
+        module.heap().subarray(out, out + 4).fill(0, 0, 4);
+        let result = status(call(&module.init, &[key.into(), out.into()]));
+        // Opaque to Rust, and only ever handed back to the module.
+        let handle = module.read_u32(out);
+        module.free(key);
+        module.free(out);
+        unsafe { *object = handle as usize as *mut pv_cobra };
+        result
+    })
+}
+
+/// Does nothing on a thread without the module, which can't be reached from
+/// there, so the instance is leaked.
+pub(crate) unsafe fn pv_cobra_delete(object: *mut pv_cobra) {
+    MODULE.with(|cell| {
+        if let Some(module) = cell.get() {
+            let _ = module.delete.call1(&JsValue::NULL, &handle(object));
+        }
+    })
+}
+
+pub(crate) unsafe fn pv_cobra_process(
+    object: *mut pv_cobra,
+    pcm: *const i16,
+    is_voiced: *mut f32,
+) -> pv_status_t {
+    with_module(|module| {
+        let pcm = unsafe { slice::from_raw_parts(pcm, module.frame_length as usize) };
+        let buffer = module.heap().buffer();
+        Int16Array::new_with_byte_offset_and_length(&buffer, module.pcm, pcm.len() as u32)
+            .copy_from(pcm);
+        let result = status(call(
+            &module.process,
+            &[handle(object), module.pcm.into(), module.is_voiced.into()],
+        ));
+        // Looked up again, in case processing grew the memory.
+        let buffer = module.heap().buffer();
+        let confidence =
+            Float32Array::new_with_byte_offset_and_length(&buffer, module.is_voiced, 1)
+                .get_index(0);
+        unsafe { *is_voiced = confidence };
+        result
+    })
+}
+
+pub(crate) unsafe fn pv_cobra_frame_length() -> i32 {
+    with_module(|module| module.frame_length)
+}
+
+pub(crate) unsafe fn pv_cobra_version() -> *const c_char {
+    with_module(|module| module.version.as_ptr())
+}
@@ -0,0 +1,206 @@
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use hound::{SampleFormat, WavReader, WavSpec};
+
+use crate::downmix::{Downmix, downmix_into};
+use crate::{
+    CancellationToken, Cobra, Confidence, Error, IntoPcm16, Padding, Result, frames_to_duration,
+};
+
+impl From<hound::Error> for Error {
+    fn from(err: hound::Error) -> Self {
+        match err {
+            hound::Error::IoError(err) => Error::from(err),
+            err => Error::UnsupportedFormat {
+                reason: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Runs the WAV file at `path` through `cobra`, returning the start time and
+/// confidence of every frame. Multi-channel audio is averaged down to mono,
+/// and a trailing partial frame is zero-padded. The file must already be at
+/// Cobra's sample rate.
+pub fn process_file<P: AsRef<Path>>(
+    path: P,
+    cobra: &mut Cobra,
+) -> Result<Vec<(Duration, Confidence)>> {
+    process_file_with(path, cobra, Downmix::Average)
+}
+
+/// Like [`process_file`], but reduces multi-channel audio as per `channels`,
+/// e.g. to analyze one speaker of an interview recorded with a voice per
+/// channel.
+pub fn process_file_with<P: AsRef<Path>>(
+    path: P,
+    cobra: &mut Cobra,
+    channels: Downmix,
+) -> Result<Vec<(Duration, Confidence)>> {
+    process_reader(WavReader::open(path)?, cobra, channels, None)
+}
+
+/// Like [`process_file_with`], but stops early once `token` is cancelled,
+/// returning the confidences of the frames processed up to that point.
+pub fn process_file_cancellable<P: AsRef<Path>>(
+    path: P,
+    cobra: &mut Cobra,
+    channels: Downmix,
+    token: &CancellationToken,
+) -> Result<Vec<(Duration, Confidence)>> {
+    process_reader(WavReader::open(path)?, cobra, channels, Some(token))
+}
+
+/// Like [`process_file`], but reads the WAV data from `reader`.
+pub fn process<R: Read>(reader: R, cobra: &mut Cobra) -> Result<Vec<(Duration, Confidence)>> {
+    process_with(reader, cobra, Downmix::Average)
+}
+
+/// Like [`process_file_with`], but reads the WAV data from `reader`.
+pub fn process_with<R: Read>(
+    reader: R,
+    cobra: &mut Cobra,
+    channels: Downmix,
+) -> Result<Vec<(Duration, Confidence)>> {
+    process_reader(WavReader::new(reader)?, cobra, channels, None)
+}
+
+/// Like [`process_file_cancellable`], but reads the WAV data from `reader`.
+pub fn process_cancellable<R: Read>(
+    reader: R,
+    cobra: &mut Cobra,
+    channels: Downmix,
+    token: &CancellationToken,
+) -> Result<Vec<(Duration, Confidence)>> {
+    process_reader(WavReader::new(reader)?, cobra, channels, Some(token))
+}
+
+fn process_reader<R: Read>(
+    mut reader: WavReader<R>,
+    cobra: &mut Cobra,
+    strategy: Downmix,
+    token: Option<&CancellationToken>,
+) -> Result<Vec<(Duration, Confidence)>> {
+    let spec = reader.spec();
+    validate(&spec, cobra.sample_rate() as u32)?;
+    if let Downmix::Channel(channel) = strategy
+        && channel >= spec.channels as usize
+    {
+        return Err(Error::UnsupportedFormat {
+            reason: format!(
+                "channel {} requested, but the file has {} channels",
+                channel, spec.channels
+            ),
+        });
+    }
+    let channels = spec.channels as usize;
+    let frame_length = cobra.frame_length() as usize;
+
+    let bits = spec.bits_per_sample;
+    let mut samples: Box<dyn Iterator<Item = hound::Result<i16>>> = match spec.sample_format {
+        SampleFormat::Int => Box::new(
+            reader
+                .samples::<i32>()
+                .map(move |sample| sample.map(|s| to_i16(s, bits))),
+        ),
+        SampleFormat::Float => Box::new(
+            reader
+                .samples::<f32>()
+                .map(|sample| sample.map(IntoPcm16::into_pcm16)),
+        ),
+    };
+    let mut interleaved = Vec::with_capacity(frame_length * channels);
+    let mut frame = Vec::with_capacity(frame_length);
+    let mut confidences = Vec::new();
+    loop {
+        if token.is_some_and(CancellationToken::is_cancelled) {
+            return Ok(confidences);
+        }
+        interleaved.clear();
+        for sample in samples.by_ref().take(frame_length * channels) {
+            interleaved.push(sample?);
+        }
+        frame.clear();
+        downmix_into(&interleaved, channels, strategy, &mut frame);
+        if frame.is_empty() {
+            return Ok(confidences);
+        }
+        let at = frames_to_duration(confidences.len() as u64);
+        let (confidence, _) = cobra.process_partial(&frame, Padding::Zero)?;
+        confidences.push((at, confidence));
+    }
+}
+
+fn validate(spec: &WavSpec, sample_rate: u32) -> Result<()> {
+    if spec.sample_rate != sample_rate {
+        return Err(Error::UnsupportedFormat {
+            reason: format!(
+                "sample rate is {} Hz, expected {} Hz",
+                spec.sample_rate, sample_rate
+            ),
+        });
+    }
+    if spec.channels == 0 {
+        return Err(Error::UnsupportedFormat {
+            reason: "no channels".to_string(),
+        });
+    }
+    match (spec.sample_format, spec.bits_per_sample) {
+        (SampleFormat::Int, 8 | 16 | 24) | (SampleFormat::Float, 32) => Ok(()),
+        (format, bits) => Err(Error::UnsupportedFormat {
+            reason: format!("{}-bit {:?} samples", bits, format),
+        }),
+    }
+}
+
+/// Scales an integer sample of the given bit depth to 16 bits.
+fn to_i16(sample: i32, bits: u16) -> i16 {
+    if bits <= 16 {
+        (sample << (16 - bits)) as i16
+    } else {
+        (sample >> (bits - 16)) as i16
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_to_i16() {
+        assert_eq!(to_i16(-128, 8), i16::MIN);
+        assert_eq!(to_i16(127, 8), 127 << 8);
+        assert_eq!(to_i16(1234, 16), 1234);
+        assert_eq!(to_i16(0x123456, 24), 0x1234);
+        assert_eq!(to_i16(-0x800000, 24), i16::MIN);
+    }
+
+    #[test]
+    fn check_validate() {
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        assert!(validate(&spec, 16000).is_ok());
+        assert!(matches!(
+            validate(&spec, 8000),
+            Err(Error::UnsupportedFormat { .. })
+        ));
+        let spec = WavSpec {
+            sample_format: SampleFormat::Float,
+            bits_per_sample: 32,
+            ..spec
+        };
+        assert!(validate(&spec, 16000).is_ok());
+        let spec = WavSpec {
+            sample_format: SampleFormat::Int,
+            bits_per_sample: 32,
+            ..spec
+        };
+        assert!(validate(&spec, 16000).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use crate::{Confidence, duration_to_frames, frames_to_duration};
+
+/// Confidence figures reduced over one window of frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowSummary {
+    pub start: Duration,
+    pub end: Duration,
+    pub mean: Confidence,
+    pub max: Confidence,
+    /// The configured percentile, using the nearest-rank method.
+    pub percentile: Confidence,
+}
+
+/// Reduces per-frame confidences into coarser fixed-length windows, e.g. for
+/// plotting or alerting at one-second granularity.
+#[derive(Debug, Clone)]
+pub struct WindowAggregator {
+    window_frames: usize,
+    percentile: f32,
+    frames: u64,
+    window: Vec<Confidence>,
+}
+
+impl WindowAggregator {
+    pub fn new(window: Duration) -> Self {
+        let window_frames = duration_to_frames(window).max(1) as usize;
+        WindowAggregator {
+            window_frames,
+            percentile: 0.9,
+            frames: 0,
+            window: Vec::with_capacity(window_frames),
+        }
+    }
+
+    /// Sets which percentile is reported, between 0 and 1. Defaults to 0.9.
+    pub fn with_percentile(mut self, percentile: f32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&percentile),
+            "percentile must be between 0 and 1"
+        );
+        self.percentile = percentile;
+        self
+    }
+
+    /// Feeds the confidence of the next frame, returning a summary once a
+    /// window has filled up.
+    pub fn push(&mut self, confidence: Confidence) -> Option<WindowSummary> {
+        self.window.push(confidence);
+        if self.window.len() < self.window_frames {
+            return None;
+        }
+        self.finish()
+    }
+
+    /// Summarizes the frames of the current, possibly incomplete, window.
+    /// Returns `None` if no frames have been pushed since the last summary.
+    pub fn finish(&mut self) -> Option<WindowSummary> {
+        if self.window.is_empty() {
+            return None;
+        }
+        let start = self.frames;
+        self.frames += self.window.len() as u64;
+        let mean = self.window.iter().map(|c| c.get()).sum::<f32>() / self.window.len() as f32;
+        self.window.sort_unstable();
+        let rank = (self.percentile * self.window.len() as f32).ceil() as usize;
+        let summary = WindowSummary {
+            start: frames_to_duration(start),
+            end: frames_to_duration(self.frames),
+            mean: Confidence::new(mean),
+            max: self.window[self.window.len() - 1],
+            percentile: self.window[rank.clamp(1, self.window.len()) - 1],
+        };
+        self.window.clear();
+        Some(summary)
+    }
+
+    /// Adapts an iterator of confidences into an iterator of summaries,
+    /// including a final summary of any incomplete window.
+    pub fn summaries<I: IntoIterator<Item = Confidence>>(
+        &mut self,
+        confidences: I,
+    ) -> Summaries<'_, I::IntoIter> {
+        Summaries {
+            aggregator: self,
+            confidences: confidences.into_iter(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.frames = 0;
+        self.window.clear();
+    }
+}
+
+/// Iterator returned by [`WindowAggregator::summaries`].
+pub struct Summaries<'a, I> {
+    aggregator: &'a mut WindowAggregator,
+    confidences: I,
+}
+
+impl<I: Iterator<Item = Confidence>> Iterator for Summaries<'_, I> {
+    type Item = WindowSummary;
+
+    fn next(&mut self) -> Option<WindowSummary> {
+        for confidence in self.confidences.by_ref() {
+            if let Some(summary) = self.aggregator.push(confidence) {
+                return Some(summary);
+            }
+        }
+        self.aggregator.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_summaries() {
+        let mut aggregator = WindowAggregator::new(frames_to_duration(4)).with_percentile(0.5);
+        let confidences = [0.1, 0.4, 0.2, 0.3, 0.5, 0.9]
+            .into_iter()
+            .map(Confidence::new);
+        let summaries: Vec<WindowSummary> = aggregator.summaries(confidences).collect();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].start, Duration::ZERO);
+        assert_eq!(summaries[0].end, frames_to_duration(4));
+        assert!((summaries[0].mean.get() - 0.25).abs() < 1e-6);
+        assert_eq!(summaries[0].max, Confidence::new(0.4));
+        assert_eq!(summaries[0].percentile, Confidence::new(0.2));
+        assert_eq!(summaries[1].start, frames_to_duration(4));
+        assert_eq!(summaries[1].end, frames_to_duration(6));
+        assert_eq!(summaries[1].max, Confidence::new(0.9));
+    }
+}
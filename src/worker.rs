@@ -0,0 +1,742 @@
+use std::collections::VecDeque;
+use std::io;
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{CobraStream, Confidence, Error, Instant, Padding, Result, frames_to_duration, lock};
+
+/// The results sent back by a [`Worker`]: the start time and confidence of
+/// every frame, or the error that stopped it.
+pub type Confidences = Receiver<Result<(Duration, Confidence)>>;
+
+/// What a [`FrameSender`] does with new audio when the worker's queue is
+/// full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackpressurePolicy {
+    /// Wait for the worker to make room.
+    #[default]
+    Block,
+    /// Discard the oldest queued chunk to make room.
+    DropOldest,
+    /// Discard the new chunk.
+    DropNewest,
+}
+
+/// Scheduling priority of a worker thread. On small boards, raising it keeps
+/// the worker from losing the CPU to other work and falling behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThreadPriority {
+    /// Whatever the operating system gives new threads.
+    #[default]
+    Normal,
+    /// A nice value, from -20 (highest priority) to 19 (lowest). Negative
+    /// values usually need elevated privileges. Only supported on Linux.
+    Nice(i32),
+    /// Real-time `SCHED_FIFO` scheduling at the given priority, from 1 to
+    /// 99. Usually needs elevated privileges or an `rtprio` limit. Only
+    /// supported on Unix.
+    RealTime(i32),
+}
+
+impl ThreadPriority {
+    /// Applies the priority to the calling thread.
+    fn apply(self) -> io::Result<()> {
+        match self {
+            ThreadPriority::Normal => Ok(()),
+            #[cfg(target_os = "linux")]
+            ThreadPriority::Nice(nice) => {
+                // On Linux, each thread has a nice value of its own.
+                let tid = unsafe { libc::gettid() };
+                if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) } != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            }
+            #[cfg(unix)]
+            ThreadPriority::RealTime(priority) => {
+                let param = libc::sched_param {
+                    sched_priority: priority,
+                };
+                let status = unsafe {
+                    libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param)
+                };
+                if status != 0 {
+                    return Err(io::Error::from_raw_os_error(status));
+                }
+                Ok(())
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "thread priority not supported on this platform",
+            )),
+        }
+    }
+}
+
+/// What a [`Worker`] got through before it was shut down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkerStats {
+    /// Number of frames processed, including a padded final frame.
+    pub frames: u64,
+    /// Number of chunks dropped because the queue was full.
+    pub dropped: u64,
+}
+
+/// A problem spotted by a worker's watchdog, see [`WorkerBuilder::watchdog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stall {
+    /// Audio is waiting, but no frame has been processed for this long,
+    /// e.g. because a call into the engine is stuck.
+    Processing { since: Duration },
+    /// No audio has been sent for this long, e.g. because the capture
+    /// thread died.
+    Input { since: Duration },
+}
+
+type StallCallback = Box<dyn FnMut(Stall) + Send>;
+
+/// Reports each stall once, when it has lasted for `timeout`.
+struct Watchdog {
+    timeout: Duration,
+    callback: StallCallback,
+    processing_stalled: bool,
+    input_stalled: bool,
+}
+
+impl Watchdog {
+    /// Checks the worker's progress, given the times since the worker started
+    /// at which audio was last received and last processed.
+    fn check(&mut self, now: Duration, received: Duration, progressed: Duration) {
+        let waiting = received > progressed;
+        let since = now.saturating_sub(progressed);
+        if waiting && since >= self.timeout {
+            if !self.processing_stalled {
+                self.processing_stalled = true;
+                (self.callback)(Stall::Processing { since });
+            }
+        } else {
+            self.processing_stalled = false;
+        }
+        let since = now.saturating_sub(received);
+        if since >= self.timeout {
+            if !self.input_stalled {
+                self.input_stalled = true;
+                (self.callback)(Stall::Input { since });
+            }
+        } else {
+            self.input_stalled = false;
+        }
+    }
+}
+
+/// A chunk of audio awaiting processing.
+#[derive(Debug, PartialEq, Eq)]
+struct Chunk {
+    pcm: Vec<i16>,
+    /// Number of samples dropped just before this chunk, which still count
+    /// towards its timestamps.
+    skipped: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    chunks: VecDeque<Chunk>,
+    /// Number of samples dropped since the last chunk was queued.
+    skipped: u64,
+    senders: usize,
+    /// No more audio is accepted, but what's queued is still processed.
+    closed: bool,
+    /// The worker thread has exited.
+    stopped: bool,
+}
+
+/// The queue between the senders and the worker thread.
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+    capacity: Option<usize>,
+    policy: BackpressurePolicy,
+    dropped: AtomicU64,
+    processed: AtomicU64,
+    // For the watchdog: nanoseconds after `epoch` at which audio was last
+    // received, and at which the worker last finished processing some.
+    epoch: Instant,
+    received_at: AtomicU64,
+    progressed_at: AtomicU64,
+}
+
+impl Shared {
+    fn new(capacity: Option<usize>, policy: BackpressurePolicy) -> Self {
+        Shared {
+            state: Mutex::default(),
+            changed: Condvar::new(),
+            capacity,
+            policy,
+            dropped: AtomicU64::new(0),
+            processed: AtomicU64::new(0),
+            epoch: Instant::now(),
+            received_at: AtomicU64::new(0),
+            progressed_at: AtomicU64::new(0),
+        }
+    }
+
+    fn elapsed(&self) -> u64 {
+        self.epoch.elapsed().as_nanos() as u64
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+
+    fn send(&self, pcm: Vec<i16>) -> Result<()> {
+        let mut state = self.lock();
+        if let Some(capacity) = self.capacity {
+            while !state.closed && !state.stopped && state.chunks.len() >= capacity {
+                match self.policy {
+                    BackpressurePolicy::Block => {
+                        state = self
+                            .changed
+                            .wait(state)
+                            .unwrap_or_else(PoisonError::into_inner);
+                    }
+                    BackpressurePolicy::DropOldest => {
+                        if let Some(oldest) = state.chunks.pop_front() {
+                            let skipped = oldest.skipped + oldest.pcm.len() as u64;
+                            match state.chunks.front_mut() {
+                                Some(next) => next.skipped += skipped,
+                                None => state.skipped += skipped,
+                            }
+                        }
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    BackpressurePolicy::DropNewest => {
+                        state.skipped += pcm.len() as u64;
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        if state.closed || state.stopped {
+            return Err(Error::WorkerStopped);
+        }
+        self.received_at.store(self.elapsed(), Ordering::Relaxed);
+        let skipped = mem::take(&mut state.skipped);
+        state.chunks.push_back(Chunk { pcm, skipped });
+        drop(state);
+        self.changed.notify_all();
+        Ok(())
+    }
+
+    /// Waits for the next chunk, or returns `None` once the queue is empty
+    /// and either every sender has been dropped or the queue was closed.
+    fn recv(&self) -> Option<Chunk> {
+        let mut state = self.lock();
+        loop {
+            if let Some(chunk) = state.chunks.pop_front() {
+                drop(state);
+                self.changed.notify_all();
+                return Some(chunk);
+            }
+            if state.senders == 0 || state.closed {
+                return None;
+            }
+            state = self
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.changed.notify_all();
+    }
+
+    fn stop(&self) {
+        self.lock().stopped = true;
+        self.changed.notify_all();
+    }
+
+    /// Runs `watchdog` every `interval` until the worker thread exits.
+    fn watch(&self, mut watchdog: Watchdog, interval: Duration) {
+        let mut state = self.lock();
+        while !state.stopped {
+            state = self
+                .changed
+                .wait_timeout(state, interval)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+            if state.closed {
+                // Input is expected to stop once the worker is shutting down.
+                watchdog.input_stalled = true;
+            }
+            watchdog.check(
+                Duration::from_nanos(self.elapsed()),
+                Duration::from_nanos(self.received_at.load(Ordering::Relaxed)),
+                Duration::from_nanos(self.progressed_at.load(Ordering::Relaxed)),
+            );
+        }
+    }
+
+    /// Waits up to `timeout` for the worker thread to exit, returning whether
+    /// it did.
+    fn wait_stopped(&self, timeout: Duration) -> bool {
+        let state = self.lock();
+        let (state, _) = self
+            .changed
+            .wait_timeout_while(state, timeout, |state| !state.stopped)
+            .unwrap_or_else(PoisonError::into_inner);
+        state.stopped
+    }
+}
+
+/// A cheap, cloneable handle for sending audio to a [`Worker`], e.g. from an
+/// audio callback.
+#[derive(Debug)]
+pub struct FrameSender {
+    shared: Arc<Shared>,
+}
+
+impl FrameSender {
+    fn new(shared: Arc<Shared>) -> Self {
+        shared.lock().senders += 1;
+        FrameSender { shared }
+    }
+
+    /// Queues a chunk of audio of any length for processing. If the queue
+    /// is full, this waits or drops audio as per the worker's
+    /// [`BackpressurePolicy`].
+    pub fn send(&self, pcm: Vec<i16>) -> Result<()> {
+        self.shared.send(pcm)
+    }
+
+    /// Number of chunks dropped so far because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Clone for FrameSender {
+    fn clone(&self) -> Self {
+        FrameSender::new(Arc::clone(&self.shared))
+    }
+}
+
+impl Drop for FrameSender {
+    fn drop(&mut self) {
+        self.shared.lock().senders -= 1;
+        self.shared.changed.notify_all();
+    }
+}
+
+/// Configures how a [`Worker`] is started.
+pub struct WorkerBuilder {
+    stream: CobraStream,
+    capacity: Option<usize>,
+    policy: BackpressurePolicy,
+    priority: ThreadPriority,
+    watchdog: Option<Watchdog>,
+}
+
+impl WorkerBuilder {
+    pub fn new(stream: CobraStream) -> Self {
+        WorkerBuilder {
+            stream,
+            capacity: None,
+            policy: BackpressurePolicy::default(),
+            priority: ThreadPriority::default(),
+            watchdog: None,
+        }
+    }
+
+    /// Limits the queue to `chunks` chunks of audio awaiting processing.
+    /// By default the queue is unbounded.
+    pub fn capacity(mut self, chunks: usize) -> Self {
+        assert!(chunks > 0, "capacity must be non-zero");
+        self.capacity = Some(chunks);
+        self
+    }
+
+    /// What to do when the queue is full. Defaults to
+    /// [`BackpressurePolicy::Block`].
+    pub fn backpressure(mut self, policy: BackpressurePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Runs the worker thread at `priority`. If it cannot be applied,
+    /// [`spawn`](Self::spawn) fails with the operating system's error.
+    pub fn priority(mut self, priority: ThreadPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Watches the worker from a separate thread and calls `callback` when
+    /// queued audio hasn't been processed, or no audio has arrived, for
+    /// `timeout`. Each stall is reported once, when it has lasted `timeout`.
+    /// Panics if `timeout` is zero.
+    pub fn watchdog<F>(mut self, timeout: Duration, callback: F) -> Self
+    where
+        F: FnMut(Stall) + Send + 'static,
+    {
+        assert!(!timeout.is_zero(), "watchdog timeout must be non-zero");
+        self.watchdog = Some(Watchdog {
+            timeout,
+            callback: Box::new(callback),
+            processing_stalled: false,
+            input_stalled: false,
+        });
+        self
+    }
+
+    /// Starts the worker thread, returning it along with the receiving end
+    /// of its results.
+    pub fn spawn(self) -> Result<(Worker, Confidences)> {
+        let shared = Arc::new(Shared::new(self.capacity, self.policy));
+        let (results, confidences) = mpsc::channel();
+        let (started, startup) = mpsc::sync_channel(1);
+        let stream = self.stream;
+        let priority = self.priority;
+        let queue = Arc::clone(&shared);
+        let sender = FrameSender::new(shared);
+        let thread = thread::Builder::new()
+            .name("cobra-worker".to_string())
+            .spawn(move || {
+                if let Err(err) = priority.apply() {
+                    let _ = started.send(Err(err));
+                    return Err(Error::WorkerStopped);
+                }
+                let _ = started.send(Ok(()));
+                run(stream, queue, results)
+            })?;
+        startup.recv().map_err(|_| Error::WorkerStopped)??;
+        if let Some(watchdog) = self.watchdog {
+            let shared = Arc::clone(&sender.shared);
+            // Checking any more often would just spin.
+            let interval = (watchdog.timeout / 4).max(Duration::from_millis(1));
+            thread::Builder::new()
+                .name("cobra-watchdog".to_string())
+                .spawn(move || shared.watch(watchdog, interval))?;
+        }
+        Ok((Worker { sender, thread }, confidences))
+    }
+}
+
+/// A dedicated thread that owns a [`CobraStream`] and processes the audio
+/// sent to it, so that capture code never waits on the engine.
+#[derive(Debug)]
+pub struct Worker {
+    sender: FrameSender,
+    thread: JoinHandle<Result<CobraStream>>,
+}
+
+impl Worker {
+    /// Starts a worker thread for `stream` with an unbounded queue. Use
+    /// [`Cobra::spawn_worker`] to start one with the default stream
+    /// configuration, or [`Worker::builder`] to bound the queue.
+    ///
+    /// [`Cobra::spawn_worker`]: crate::Cobra::spawn_worker
+    pub fn spawn(stream: CobraStream) -> Result<(Self, Confidences)> {
+        WorkerBuilder::new(stream).spawn()
+    }
+
+    pub fn builder(stream: CobraStream) -> WorkerBuilder {
+        WorkerBuilder::new(stream)
+    }
+
+    /// Queues a chunk of audio of any length for processing.
+    pub fn send(&self, pcm: Vec<i16>) -> Result<()> {
+        self.sender.send(pcm)
+    }
+
+    /// Another handle for sending audio to this worker.
+    pub fn sender(&self) -> FrameSender {
+        self.sender.clone()
+    }
+
+    /// Number of chunks dropped so far because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.sender.dropped()
+    }
+
+    /// Waits for the worker to process everything sent to it, including a
+    /// zero-padded final frame, and returns its stream. The worker only
+    /// stops once every [`FrameSender`] has been dropped too.
+    pub fn join(self) -> Result<CobraStream> {
+        drop(self.sender);
+        self.thread.join().map_err(|_| Error::WorkerStopped)?
+    }
+
+    /// Stops accepting audio from any sender, then waits up to `timeout` for
+    /// the worker to process what's already queued, including a zero-padded
+    /// final frame, and exit. Every confidence, the final frame's included,
+    /// has been sent by the time this returns, so anything reading them, such
+    /// as a [`SpeechSegmenter`], can be finished once they're received. If it
+    /// takes longer, this gives up with [`Error::ShutdownTimedOut`] and leaves
+    /// the thread to finish on its own.
+    ///
+    /// [`SpeechSegmenter`]: crate::segmenter::SpeechSegmenter
+    pub fn shutdown(self, timeout: Duration) -> Result<(CobraStream, WorkerStats)> {
+        let Worker { sender, thread } = self;
+        let shared = Arc::clone(&sender.shared);
+        drop(sender);
+        shared.close();
+        if !shared.wait_stopped(timeout) {
+            return Err(Error::ShutdownTimedOut { timeout });
+        }
+        let stream = thread.join().map_err(|_| Error::WorkerStopped)??;
+        let stats = WorkerStats {
+            frames: shared.processed.load(Ordering::Relaxed),
+            dropped: shared.dropped.load(Ordering::Relaxed),
+        };
+        Ok((stream, stats))
+    }
+}
+
+/// Marks the queue as stopped when the worker exits, even by panicking, so
+/// that blocked senders give up.
+struct StopOnDrop(Arc<Shared>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.stop();
+    }
+}
+
+/// What a worker thread processes audio with.
+trait Process {
+    fn push(&mut self, pcm: &[i16]) -> Result<Vec<Confidence>>;
+
+    /// Processes what's left over as a final frame.
+    fn finish(&mut self) -> Result<Vec<Confidence>>;
+
+    /// Duration of `samples` samples of the audio pushed.
+    fn input_duration(&self, samples: u64) -> Duration;
+
+    /// Start time of the `frame`th frame processed.
+    fn frame_start(frame: u64) -> Duration;
+}
+
+impl Process for CobraStream {
+    fn push(&mut self, pcm: &[i16]) -> Result<Vec<Confidence>> {
+        CobraStream::push(self, pcm)
+    }
+
+    fn finish(&mut self) -> Result<Vec<Confidence>> {
+        CobraStream::finish(self, Padding::Zero)
+    }
+
+    fn input_duration(&self, samples: u64) -> Duration {
+        let nanos = samples as u128 * 1_000_000_000 / self.input_rate() as u128;
+        Duration::from_nanos(nanos as u64)
+    }
+
+    fn frame_start(frame: u64) -> Duration {
+        frames_to_duration(frame)
+    }
+}
+
+fn run<P: Process>(
+    mut stream: P,
+    queue: Arc<Shared>,
+    results: Sender<Result<(Duration, Confidence)>>,
+) -> Result<P> {
+    let queue = StopOnDrop(queue);
+    let processed = &queue.0.processed;
+    // Audio dropped from the queue still passed, so it pushes back the
+    // timestamps of everything after it.
+    let mut skipped = 0;
+    let mut offset = Duration::ZERO;
+    let send = |offset: Duration, confidences: Result<Vec<Confidence>>| match confidences {
+        Ok(confidences) => {
+            queue
+                .0
+                .progressed_at
+                .store(queue.0.elapsed(), Ordering::Relaxed);
+            for confidence in confidences {
+                let at = P::frame_start(processed.fetch_add(1, Ordering::Relaxed)) + offset;
+                // Keep going if nobody is listening, so `join` still works.
+                let _ = results.send(Ok((at, confidence)));
+            }
+            Ok(())
+        }
+        Err(err) => {
+            let _ = results.send(Err(err.clone()));
+            Err(err)
+        }
+    };
+    while let Some(chunk) = queue.0.recv() {
+        if chunk.skipped > 0 {
+            skipped += chunk.skipped;
+            offset = stream.input_duration(skipped);
+        }
+        send(offset, stream.push(&chunk.pcm))?;
+    }
+    send(offset, stream.finish())?;
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Reports how full each frame of four samples is, the tail included.
+    struct Counter {
+        buf: Vec<i16>,
+    }
+
+    impl Process for Counter {
+        fn push(&mut self, pcm: &[i16]) -> Result<Vec<Confidence>> {
+            self.buf.extend_from_slice(pcm);
+            let frames = self.buf.len() / 4;
+            self.buf.drain(..frames * 4);
+            Ok(vec![Confidence::new(1.0); frames])
+        }
+
+        fn finish(&mut self) -> Result<Vec<Confidence>> {
+            let tail = self.buf.len();
+            self.buf.clear();
+            Ok((tail > 0)
+                .then(|| Confidence::new(tail as f32 / 4.0))
+                .into_iter()
+                .collect())
+        }
+
+        // A sample a millisecond.
+        fn input_duration(&self, samples: u64) -> Duration {
+            Duration::from_millis(samples)
+        }
+
+        fn frame_start(frame: u64) -> Duration {
+            Duration::from_millis(frame * 4)
+        }
+    }
+
+    fn queued(shared: &Shared) -> Vec<i16> {
+        shared
+            .lock()
+            .chunks
+            .iter()
+            .map(|chunk| chunk.pcm[0])
+            .collect()
+    }
+
+    #[test]
+    fn check_drop_policies() {
+        let shared = Shared::new(Some(2), BackpressurePolicy::DropOldest);
+        for i in 0..4 {
+            shared.send(vec![i]).unwrap();
+        }
+        assert_eq!(queued(&shared), [2, 3]);
+        assert_eq!(shared.dropped.load(Ordering::Relaxed), 2);
+
+        let shared = Shared::new(Some(2), BackpressurePolicy::DropNewest);
+        for i in 0..4 {
+            shared.send(vec![i]).unwrap();
+        }
+        assert_eq!(queued(&shared), [0, 1]);
+        assert_eq!(shared.dropped.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn check_blocked_send_resumes() {
+        let shared = Arc::new(Shared::new(Some(1), BackpressurePolicy::Block));
+        let sender = FrameSender::new(Arc::clone(&shared));
+        sender.send(vec![0]).unwrap();
+        let blocked = thread::spawn(move || sender.send(vec![1]));
+        assert_eq!(shared.recv().unwrap().pcm, [0]);
+        blocked.join().unwrap().unwrap();
+        assert_eq!(shared.recv().unwrap().pcm, [1]);
+        // All senders are gone, so there's nothing more to wait for.
+        assert_eq!(shared.recv(), None);
+        shared.stop();
+        assert!(shared.send(vec![2]).is_err());
+    }
+
+    #[test]
+    fn check_watchdog_reports_each_stall_once() {
+        let stalls = Arc::new(Mutex::new(Vec::new()));
+        let mut watchdog = Watchdog {
+            timeout: Duration::from_secs(2),
+            callback: Box::new({
+                let stalls = Arc::clone(&stalls);
+                move |stall| stalls.lock().unwrap().push(stall)
+            }),
+            processing_stalled: false,
+            input_stalled: false,
+        };
+        let secs = Duration::from_secs;
+        // Audio arrived at 1s, and was last processed at 0s.
+        watchdog.check(secs(1), secs(1), secs(0));
+        watchdog.check(secs(2), secs(2), secs(0));
+        watchdog.check(secs(3), secs(3), secs(0));
+        // Processing catches up, then the input dries up.
+        watchdog.check(secs(4), secs(3), secs(4));
+        watchdog.check(secs(5), secs(3), secs(4));
+        assert_eq!(
+            *stalls.lock().unwrap(),
+            [
+                Stall::Processing { since: secs(2) },
+                Stall::Input { since: secs(2) }
+            ]
+        );
+    }
+
+    #[test]
+    fn check_close_drains_queue() {
+        let shared = Arc::new(Shared::new(None, BackpressurePolicy::Block));
+        let sender = FrameSender::new(Arc::clone(&shared));
+        sender.send(vec![0]).unwrap();
+        shared.close();
+        assert!(sender.send(vec![1]).is_err());
+        assert_eq!(shared.recv().unwrap().pcm, [0]);
+        assert_eq!(shared.recv(), None);
+        assert!(!shared.wait_stopped(Duration::from_millis(1)));
+        shared.stop();
+        assert!(shared.wait_stopped(Duration::ZERO));
+    }
+
+    #[test]
+    fn check_close_flushes_tail() {
+        let shared = Arc::new(Shared::new(None, BackpressurePolicy::Block));
+        let sender = FrameSender::new(Arc::clone(&shared));
+        sender.send(vec![0; 6]).unwrap();
+        shared.close();
+        let (results, confidences) = mpsc::channel();
+        let counter = run(Counter { buf: Vec::new() }, shared, results).unwrap();
+        assert!(counter.buf.is_empty());
+        let confidences: Vec<f32> = confidences
+            .iter()
+            .map(|result| result.unwrap().1.get())
+            .collect();
+        assert_eq!(confidences, [1.0, 0.5]);
+    }
+
+    #[test]
+    fn check_dropped_audio_counts_towards_timestamps() {
+        let shared = Shared::new(Some(1), BackpressurePolicy::DropNewest);
+        shared.send(vec![0; 4]).unwrap();
+        shared.send(vec![1; 4]).unwrap();
+        assert_eq!(shared.recv().unwrap().skipped, 0);
+        shared.send(vec![2; 4]).unwrap();
+        assert_eq!(shared.recv().unwrap().skipped, 4);
+
+        let shared = Arc::new(Shared::new(Some(1), BackpressurePolicy::DropOldest));
+        let sender = FrameSender::new(Arc::clone(&shared));
+        sender.send(vec![0; 4]).unwrap();
+        sender.send(vec![1; 4]).unwrap();
+        shared.close();
+        let (results, confidences) = mpsc::channel();
+        run(Counter { buf: Vec::new() }, shared, results).unwrap();
+        let starts: Vec<Duration> = confidences.iter().map(|result| result.unwrap().0).collect();
+        // The first frame was dropped, so the one processed started at 4ms.
+        assert_eq!(starts, [Duration::from_millis(4)]);
+    }
+}